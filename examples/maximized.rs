@@ -0,0 +1,17 @@
+mod common;
+
+/*
+  This example showcases a window which starts out maximized.
+*/
+
+fn main() {
+  common::init_log(env!("CARGO_CRATE_NAME"));
+  let window = witer::Window::builder()
+    .with_title("maximized")
+    .with_maximized(true)
+    .build()
+    .unwrap();
+  for m in &window {
+    tracing::trace!("{m:?}");
+  }
+}
@@ -246,7 +246,7 @@ impl State {
   /// [`Self::take_egui_input`].
   pub fn on_window_event(&mut self, window: &Window, message: &Message) -> EventResponse {
     match message {
-      Message::ScaleFactorChanged(scale_factor) => {
+      Message::ScaleFactorChanged { scale_factor, .. } => {
         let native_pixels_per_point = *scale_factor as f32;
 
         self
@@ -268,8 +268,10 @@ impl State {
           consumed: self.egui_ctx.wants_pointer_input(),
         }
       }
-      Message::MouseWheel { delta_x, delta_y } => {
-        self.on_mouse_wheel(window, *delta_x, *delta_y);
+      Message::MouseWheel { delta, phase } => {
+        if *phase != ScrollPhase::Ended {
+          self.on_mouse_wheel(window, *delta);
+        }
         EventResponse {
           repaint: true,
           consumed: self.egui_ctx.wants_pointer_input(),
@@ -425,10 +427,16 @@ impl State {
 
       // Things that may require repaint:
       Message::Paint
+      | Message::RedrawRequested
       // | WindowEvent::Occluded(_)
       | Message::Resized(_)
       | Message::Moved(_)
-      // | WindowEvent::ThemeChanged(_)
+      | Message::StateChanged { .. }
+      | Message::SizeMoveEnded
+      | Message::Occluded(_)
+      | Message::ThemeChanged(_)
+      | Message::AccentColorChanged(_)
+      | Message::HighContrastChanged(_)
       // | WindowEvent::TouchpadPressure { .. }
       | Message::CloseRequested => EventResponse {
         repaint: true,
@@ -440,8 +448,25 @@ impl State {
         Message::RawInput(_) |
         Message::Created { .. } |
         Message::BoundsChanged { .. } |
+        Message::SizeMoveStarted |
         Message::Command |
-        Message::SystemCommand => EventResponse {
+        Message::MenuCommand(_) |
+        Message::SystemCommand(_) |
+        Message::FileHovered(_) |
+        Message::FileHoverCancelled |
+        Message::FileDropped { .. } |
+        Message::TextDropped { .. } |
+        Message::UrlDropped { .. } |
+        Message::ClipboardChanged |
+        Message::Tray(_) |
+        Message::Suspended |
+        Message::Resumed |
+        Message::SessionLocked |
+        Message::SessionUnlocked |
+        Message::DisplayStateChanged(_) |
+        Message::Timer(_) |
+        Message::Hotkey(_) |
+        Message::KeyboardLayoutChanged { .. } => EventResponse {
         repaint: false,
         consumed: false,
       },
@@ -613,14 +638,16 @@ impl State {
   //   }
   // }
 
-  fn on_mouse_wheel(&mut self, window: &Window, delta_x: f32, delta_y: f32) {
+  fn on_mouse_wheel(&mut self, window: &Window, delta: ScrollDelta) {
     let pixels_per_point = pixels_per_point(&self.egui_ctx, window);
 
     {
-      let (unit, delta) = (
-        egui::MouseWheelUnit::Point,
-        egui::vec2(delta_x as f32, delta_y as f32) / pixels_per_point,
-      );
+      let (unit, delta) = match delta {
+        ScrollDelta::Lines { x, y } => (egui::MouseWheelUnit::Line, egui::vec2(x, y)),
+        ScrollDelta::Pixels { x, y } => {
+          (egui::MouseWheelUnit::Point, egui::vec2(x, y) / pixels_per_point)
+        }
+      };
       let modifiers = self.egui_input.modifiers;
       self.egui_input.events.push(egui::Event::MouseWheel {
         unit,
@@ -629,7 +656,13 @@ impl State {
       });
     }
 
-    let delta = egui::vec2(delta_x as f32, delta_y as f32) / pixels_per_point;
+    let delta = match delta {
+      ScrollDelta::Lines { x, y } => {
+        let points_per_scroll_line = 50.0; // Scroll speed decided by consensus
+        egui::vec2(x, y) * points_per_scroll_line
+      }
+      ScrollDelta::Pixels { x, y } => egui::vec2(x, y) / pixels_per_point,
+    };
 
     if self.egui_input.modifiers.ctrl || self.egui_input.modifiers.command {
       // Treat as zoom instead:
@@ -1,13 +1,15 @@
 use crate::raw_window_handle::RawDisplayHandle;
+#[cfg(feature = "clipboard")]
+use crate::window::clipboard;
 
 /// Handles interfacing with the OS clipboard.
 ///
-/// If the "clipboard" feature is off, or we cannot connect to the OS clipboard,
+/// If the "clipboard" feature is off, or we cannot access the OS clipboard,
 /// then a fallback clipboard that just works within the same app is used
 /// instead.
 pub struct Clipboard {
   #[cfg(feature = "clipboard")]
-  arboard: Option<arboard::Clipboard>,
+  system: clipboard::Clipboard,
   /// Fallback manual clipboard.
   clipboard: String,
 }
@@ -17,7 +19,7 @@ impl Clipboard {
   pub fn new(_raw_display_handle: Option<RawDisplayHandle>) -> Self {
     Self {
       #[cfg(feature = "clipboard")]
-      arboard: init_arboard(),
+      system: clipboard::Clipboard::new(),
 
       clipboard: Default::default(),
     }
@@ -25,14 +27,8 @@ impl Clipboard {
 
   pub fn get(&mut self) -> Option<String> {
     #[cfg(feature = "clipboard")]
-    if let Some(clipboard) = &mut self.arboard {
-      return match clipboard.get_text() {
-        Ok(text) => Some(text),
-        Err(err) => {
-          tracing::error!("arboard paste error: {err}");
-          None
-        }
-      };
+    if let Some(text) = self.system.text() {
+      return Some(text);
     }
 
     Some(self.clipboard.clone())
@@ -40,25 +36,11 @@ impl Clipboard {
 
   pub fn set(&mut self, text: String) {
     #[cfg(feature = "clipboard")]
-    if let Some(clipboard) = &mut self.arboard {
-      if let Err(err) = clipboard.set_text(text) {
-        tracing::error!("arboard copy/cut error: {err}");
-      }
-      return;
+    match self.system.set_text(&text) {
+      Ok(()) => return,
+      Err(err) => tracing::error!("clipboard copy/cut error: {err}"),
     }
 
     self.clipboard = text;
   }
 }
-
-#[cfg(feature = "clipboard")]
-fn init_arboard() -> Option<arboard::Clipboard> {
-  tracing::trace!("Initializing arboard clipboard…");
-  match arboard::Clipboard::new() {
-    Ok(clipboard) => Some(clipboard),
-    Err(err) => {
-      tracing::warn!("Failed to initialize arboard clipboard: {err}");
-      None
-    }
-  }
-}
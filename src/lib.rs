@@ -42,8 +42,14 @@ pub mod window;
 
 // re-exports
 pub use window::{
+  clipboard::{Clipboard, ClipboardImage},
+  cursor::{CursorImage, CustomCursor},
   data::{
+    Backdrop,
+    Color,
+    CornerPreference,
     CursorMode,
+    DpiPolicy,
     Flow,
     Fullscreen,
     LogicalPosition,
@@ -51,18 +57,31 @@ pub use window::{
     PhysicalPosition,
     PhysicalSize,
     Position,
+    Rect,
+    RedrawPacing,
     Size,
     Theme,
+    TimerId,
+    UserAttention,
     Visibility,
+    WindowLevel,
   },
+  hotkey::{Hotkey, HotkeyId, HotkeyRegistration},
+  icon::Icon,
+  inhibit::{InhibitCondition, InhibitFlags, Inhibitor},
   input::{
     key::Key,
-    mouse::MouseButton,
+    layout::KeyboardLayout,
+    mouse::{MouseButton, ScrollDelta, ScrollPhase},
     state::{ButtonState, KeyState, RawKeyState},
     Input,
   },
-  message::{LoopMessage, Message, RawInputMessage},
+  menu::{Accelerator, Menu, MenuCheck, MenuEntry, MenuId, MenuItem},
+  message::{DisplayState, LoopMessage, Message, RawInputMessage, SystemCommand},
+  present::{PixelFormat, Scaling},
   settings::{WindowBuilder, WindowSettings},
+  suppress::KeySuppression,
+  tray::{Balloon, BalloonIcon, TrayEvent, TrayIcon},
   Window,
 };
 
@@ -1,7 +1,13 @@
 pub use crate::window::{
   self,
+  clipboard::{Clipboard, ClipboardImage},
+  cursor::{CursorImage, CustomCursor},
   data::{
+    Backdrop,
+    Color,
+    CornerPreference,
     CursorMode,
+    DpiPolicy,
     Flow,
     Fullscreen,
     LogicalPosition,
@@ -9,17 +15,30 @@ pub use crate::window::{
     PhysicalPosition,
     PhysicalSize,
     Position,
+    Rect,
+    RedrawPacing,
     Size,
     Theme,
+    TimerId,
+    UserAttention,
     Visibility,
+    WindowLevel,
   },
+  hotkey::{Hotkey, HotkeyId, HotkeyRegistration},
+  icon::Icon,
+  inhibit::{InhibitCondition, InhibitFlags, Inhibitor},
   input::{
     key::Key,
-    mouse::MouseButton,
+    layout::KeyboardLayout,
+    mouse::{MouseButton, ScrollDelta, ScrollPhase},
     state::{ButtonState, KeyState, RawKeyState},
     Input,
   },
-  message::{LoopMessage, Message, RawInputMessage},
+  menu::{Accelerator, Menu, MenuCheck, MenuEntry, MenuId, MenuItem},
+  message::{DisplayState, LoopMessage, Message, RawInputMessage, SystemCommand},
+  present::{PixelFormat, Scaling},
   settings::{WindowBuilder, WindowSettings},
+  suppress::KeySuppression,
+  tray::{Balloon, BalloonIcon, TrayEvent, TrayIcon},
   Window,
 };
@@ -1,5 +1,6 @@
 use std::{
   ops::BitAnd,
+  path::PathBuf,
   sync::{
     atomic::{AtomicBool, Ordering},
     OnceLock,
@@ -11,13 +12,36 @@ use windows::{
   core::{PCSTR, PCWSTR},
   Win32::{
     Devices::HumanInterfaceDevice,
-    Foundation::{HWND, NTSTATUS, RECT},
-    Graphics::Gdi::{GetDC, GetMonitorInfoW, HMONITOR, MONITORINFO, MONITORINFOEXW},
+    Foundation::{HGLOBAL, HWND, NTSTATUS, RECT},
+    Graphics::{
+      Dwm::{
+        self,
+        DwmEnableBlurBehindWindow,
+        DwmExtendFrameIntoClientArea,
+        DwmGetWindowAttribute,
+        DwmSetWindowAttribute,
+        DWMWINDOWATTRIBUTE,
+        DWM_BLURBEHIND,
+      },
+      Gdi::{
+        CreateRectRgn,
+        DeleteObject,
+        GetDC,
+        GetMonitorInfoW,
+        HMONITOR,
+        MONITORINFO,
+        MONITORINFOEXW,
+      },
+    },
     System::{
+      Com::{CoCreateInstance, CLSCTX_INPROC_SERVER},
       LibraryLoader::{GetProcAddress, LoadLibraryA},
+      Memory::{GlobalLock, GlobalUnlock},
       SystemInformation::OSVERSIONINFOW,
     },
     UI::{
+      Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW},
+      Controls::MARGINS,
       HiDpi::{self, GetDpiForMonitor, GetDpiForWindow},
       Input::{
         self,
@@ -28,12 +52,15 @@ use windows::{
         RAWINPUTDEVICE,
         RAWINPUTHEADER,
       },
+      Shell::{DragQueryFileW, ITaskbarList, TaskbarList, HDROP},
       WindowsAndMessaging::{
         self,
         ClipCursor,
         GetClipCursor,
         GetSystemMetrics,
         ShowCursor,
+        SystemParametersInfoW,
+        SYSTEM_PARAMETERS_INFO_ACTION,
         WINDOW_EX_STYLE,
         WINDOW_STYLE,
       },
@@ -46,7 +73,7 @@ use crate::{
   error::WindowError,
   prelude::{PhysicalPosition, PhysicalSize},
   window::{
-    data::{Fullscreen, Visibility},
+    data::{Color, Fullscreen, Visibility, WindowLevel},
     frame::Style,
   },
 };
@@ -164,15 +191,107 @@ pub fn is_dark_mode_supported() -> bool {
   })
 }
 
+/// Windows 10 builds before 20H1 (build 18985) expect the dark mode attribute under an
+/// undocumented index.
+pub(crate) fn is_legacy_dark_mode_attribute() -> bool {
+  matches!(windows_10_build_version(), Some(v) if v < 18985)
+}
+
+/// Corner preference and frame colors were introduced with Windows 11 (build 22000).
+pub fn is_frame_customization_supported() -> bool {
+  matches!(windows_10_build_version(), Some(v) if v >= 22000)
+}
+
+/// `DWMWA_SYSTEMBACKDROP_TYPE` was introduced with Windows 11 22H2 (build 22621). Earlier
+/// Windows 11 builds only support Mica through an undocumented attribute.
+pub fn is_system_backdrop_supported() -> bool {
+  matches!(windows_10_build_version(), Some(v) if v >= 22621)
+}
+
+pub(crate) fn set_dwm_attribute<T>(hwnd: HWND, attribute: DWMWINDOWATTRIBUTE, value: &T) {
+  if let Err(_error) = unsafe {
+    DwmSetWindowAttribute(
+      hwnd,
+      attribute,
+      value as *const T as *const std::ffi::c_void,
+      std::mem::size_of::<T>() as u32,
+    )
+  } {
+    tracing::error!("{_error}");
+  }
+}
+
+/// Read each time, as the user can switch between light and dark mode at any time.
 pub fn is_system_dark_mode_enabled() -> bool {
-  static IS_SYSTEM_DARK_MODE: OnceLock<bool> = OnceLock::new();
-  *IS_SYSTEM_DARK_MODE.get_or_init(|| {
-    let settings = UISettings::new().unwrap();
-    let foreground = settings
-      .GetColorValue(UIColorType::Foreground)
-      .unwrap_or_default();
-    is_color_light(&foreground)
-  })
+  let Ok(settings) = UISettings::new() else {
+    return false;
+  };
+  let foreground = settings
+    .GetColorValue(UIColorType::Foreground)
+    .unwrap_or_default();
+  is_color_light(&foreground)
+}
+
+/// Windows are cloaked by the desktop window manager while on another virtual desktop,
+/// among other reasons.
+pub(crate) fn is_cloaked(hwnd: HWND) -> bool {
+  let mut cloaked = 0u32;
+  let result = unsafe {
+    DwmGetWindowAttribute(
+      hwnd,
+      Dwm::DWMWA_CLOAKED,
+      &mut cloaked as *mut u32 as *mut std::ffi::c_void,
+      std::mem::size_of::<u32>() as u32,
+    )
+  };
+  result.is_ok() && cloaked != 0
+}
+
+/// Accent color chosen in the personalization settings.
+pub fn system_accent_color() -> Option<Color> {
+  let accent = UISettings::new()
+    .ok()?
+    .GetColorValue(UIColorType::Accent)
+    .ok()?;
+  Some(Color::new(accent.R, accent.G, accent.B))
+}
+
+pub fn is_high_contrast_enabled() -> bool {
+  let mut high_contrast = HIGHCONTRASTW {
+    cbSize: std::mem::size_of::<HIGHCONTRASTW>() as u32,
+    ..Default::default()
+  };
+  let result = unsafe {
+    SystemParametersInfoW(
+      WindowsAndMessaging::SPI_GETHIGHCONTRAST,
+      high_contrast.cbSize,
+      Some(&mut high_contrast as *mut HIGHCONTRASTW as *mut std::ffi::c_void),
+      Default::default(),
+    )
+  };
+  result.is_ok() && is_flag_set(high_contrast.dwFlags.0, HCF_HIGHCONTRASTON.0)
+}
+
+/// Lines or characters scrolled per wheel notch, for `SPI_GETWHEELSCROLLLINES` and
+/// `SPI_GETWHEELSCROLLCHARS`. Scrolling by pages is treated as the default of three.
+pub(crate) fn wheel_scroll_setting(action: SYSTEM_PARAMETERS_INFO_ACTION) -> u32 {
+  const DEFAULT: u32 = 3;
+  // `WHEEL_PAGESCROLL`
+  const PAGE_SCROLL: u32 = u32::MAX;
+
+  let mut value = DEFAULT;
+  let result = unsafe {
+    SystemParametersInfoW(
+      action,
+      0,
+      Some(&mut value as *mut u32 as *mut std::ffi::c_void),
+      Default::default(),
+    )
+  };
+  match result {
+    Ok(()) if value != PAGE_SCROLL => value,
+    _ => DEFAULT,
+  }
 }
 
 #[inline]
@@ -184,6 +303,7 @@ pub(crate) fn get_window_style(info: &Style) -> WINDOW_STYLE {
   let mut style = WindowsAndMessaging::WS_CAPTION
     | WindowsAndMessaging::WS_BORDER
     | WindowsAndMessaging::WS_CLIPSIBLINGS
+    | WindowsAndMessaging::WS_CLIPCHILDREN
     | WindowsAndMessaging::WS_SYSMENU;
 
   if info.resizeable {
@@ -205,6 +325,17 @@ pub(crate) fn get_window_style(info: &Style) -> WINDOW_STYLE {
     style &= !(WindowsAndMessaging::WS_CAPTION | WindowsAndMessaging::WS_BORDER);
   }
 
+  if info.child || info.popup {
+    style &= !WindowsAndMessaging::WS_OVERLAPPEDWINDOW;
+    style |= match info.child {
+      true => WindowsAndMessaging::WS_CHILD,
+      false => WindowsAndMessaging::WS_POPUP,
+    };
+    if let Visibility::Shown = info.decorations {
+      style |= WindowsAndMessaging::WS_BORDER;
+    }
+  }
+
   style
 }
 
@@ -212,6 +343,32 @@ pub(crate) fn get_window_ex_style(info: &Style) -> WINDOW_EX_STYLE {
   let mut style =
     WindowsAndMessaging::WS_EX_WINDOWEDGE | WindowsAndMessaging::WS_EX_APPWINDOW;
 
+  if info.skip_taskbar || info.tool_window || info.child || info.popup {
+    style &= !WindowsAndMessaging::WS_EX_APPWINDOW;
+  }
+
+  if info.tool_window || info.popup {
+    style |= WindowsAndMessaging::WS_EX_TOOLWINDOW;
+  }
+
+  if info.child {
+    // `WM_PARENTNOTIFY` is sent synchronously to the parent, which lives on another thread
+    style &= !WindowsAndMessaging::WS_EX_WINDOWEDGE;
+    style |= WindowsAndMessaging::WS_EX_NOPARENTNOTIFY;
+  }
+
+  if let WindowLevel::AlwaysOnTop = info.level {
+    style |= WindowsAndMessaging::WS_EX_TOPMOST;
+  }
+
+  if is_layered(info) {
+    style |= WindowsAndMessaging::WS_EX_LAYERED;
+  }
+
+  if !info.cursor_hittest {
+    style |= WindowsAndMessaging::WS_EX_TRANSPARENT;
+  }
+
   if let Some(Fullscreen::Borderless) = info.fullscreen {
     style &= !WindowsAndMessaging::WS_EX_OVERLAPPEDWINDOW;
   }
@@ -223,6 +380,89 @@ pub(crate) fn get_window_ex_style(info: &Style) -> WINDOW_EX_STYLE {
   style
 }
 
+/// Both partial opacity and click-through require the window to be layered.
+pub(crate) fn is_layered(info: &Style) -> bool {
+  info.opacity < 1.0 || !info.cursor_hittest
+}
+
+/// Enables DWM blur-behind with an empty region, which lets the client area be composited
+/// with per-pixel alpha without actually blurring anything.
+pub(crate) fn set_blur_behind(hwnd: HWND, enabled: bool) {
+  let region = unsafe { CreateRectRgn(0, 0, -1, -1) };
+  let blur_behind = DWM_BLURBEHIND {
+    dwFlags: Dwm::DWM_BB_ENABLE | Dwm::DWM_BB_BLURREGION,
+    fEnable: enabled.into(),
+    hRgnBlur: region,
+    fTransitionOnMaximized: false.into(),
+  };
+  if let Err(_error) = unsafe { DwmEnableBlurBehindWindow(hwnd, &blur_behind) } {
+    tracing::error!("{_error}");
+  }
+  unsafe { DeleteObject(region) };
+}
+
+/// Extends the DWM frame over the whole client area, or shrinks it back to the borders. System
+/// backdrops are drawn behind the frame, so they only show through the client area while it
+/// is extended.
+pub(crate) fn set_frame_extended(hwnd: HWND, extended: bool) {
+  let inset = if extended { -1 } else { 0 };
+  let margins = MARGINS {
+    cxLeftWidth: inset,
+    cxRightWidth: inset,
+    cyTopHeight: inset,
+    cyBottomHeight: inset,
+  };
+  if let Err(_error) = unsafe { DwmExtendFrameIntoClientArea(hwnd, &margins) } {
+    tracing::error!("{_error}");
+  }
+}
+
+/// Adds or removes the taskbar button of the window. COM must already be initialized on the
+/// calling thread.
+pub(crate) fn set_taskbar_tab(hwnd: HWND, shown: bool) {
+  let result = unsafe {
+    CoCreateInstance::<_, ITaskbarList>(&TaskbarList, None, CLSCTX_INPROC_SERVER)
+      .and_then(|taskbar| {
+        taskbar.HrInit()?;
+        if shown {
+          taskbar.AddTab(hwnd)
+        } else {
+          taskbar.DeleteTab(hwnd)
+        }
+      })
+  };
+
+  if let Err(_error) = result {
+    tracing::error!("{_error}");
+  }
+}
+
+/// Reads the paths of the files held by a drop handle.
+pub(crate) fn hdrop_paths(hdrop: HDROP) -> Vec<PathBuf> {
+  let count = unsafe { DragQueryFileW(hdrop, u32::MAX, None) };
+  (0..count)
+    .map(|i| {
+      let len = unsafe { DragQueryFileW(hdrop, i, None) } as usize;
+      let mut buffer = vec![0u16; len + 1];
+      unsafe { DragQueryFileW(hdrop, i, Some(&mut buffer)) };
+      PathBuf::from(String::from_utf16_lossy(&buffer[..len]))
+    })
+    .collect()
+}
+
+/// Reads a null-terminated UTF-16 string out of global memory.
+pub(crate) unsafe fn read_wide_string(hglobal: HGLOBAL) -> String {
+  unsafe {
+    let ptr = GlobalLock(hglobal) as *const u16;
+    if ptr.is_null() {
+      return String::new();
+    }
+    let string = PCWSTR(ptr).to_string().unwrap_or_default();
+    let _ = GlobalUnlock(hglobal);
+    string
+  }
+}
+
 pub(crate) fn set_cursor_clip(rect: Option<&RECT>) {
   if let Err(_e) = unsafe { ClipCursor(rect.map(|r| r as _)) } {
     tracing::error!("{_e}");
@@ -2,9 +2,9 @@ use std::{
   collections::VecDeque,
   sync::{mpsc::SyncSender, Arc, Condvar, Mutex},
   thread::JoinHandle,
+  time::Duration,
 };
 
-use cursor_icon::CursorIcon;
 #[cfg(all(feature = "rwh_05", not(feature = "rwh_06")))]
 use rwh_05::{
   HasRawDisplayHandle,
@@ -31,23 +31,31 @@ use windows::{
   Win32::{
     Foundation::*,
     Graphics::{
-      Dwm::{self, DwmSetWindowAttribute},
+      Dwm,
       Gdi::{
         self,
         EnumDisplayMonitors,
+        GetDC,
         MonitorFromPoint,
         MonitorFromWindow,
+        ReleaseDC,
         HDC,
         HMONITOR,
       },
     },
-    System::LibraryLoader::GetModuleHandleW,
+    System::{
+      LibraryLoader::GetModuleHandleW,
+      Ole::{OleInitialize, OleUninitialize},
+    },
     UI::{
       HiDpi::{
         AdjustWindowRectExForDpi,
         SetProcessDpiAwarenessContext,
+        DPI_AWARENESS_CONTEXT,
         DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE,
         DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
+        DPI_AWARENESS_CONTEXT_SYSTEM_AWARE,
+        DPI_AWARENESS_CONTEXT_UNAWARE,
       },
       WindowsAndMessaging::{
         self,
@@ -68,11 +76,20 @@ use windows::{
 };
 
 use self::{
+  clipboard::Clipboard,
   command::Command,
-  data::{CursorMode, Fullscreen, PhysicalSize, Position},
+  cursor::CursorImage,
+  data::{CursorMode, Fullscreen, PhysicalSize, Position, TimerId},
+  hotkey::{Hotkey, HotkeyRegistration},
+  inhibit::{InhibitFlags, Inhibitor},
+  input::layout::KeyboardLayout,
+  menu::{Menu, MenuId},
   message::LoopMessage,
-  settings::WindowBuilder,
+  present::{Framebuffer, PixelFormat, Scaling},
+  settings::{Parent, WindowBuilder},
   stage::Stage,
+  suppress::KeySuppression,
+  tray::{Balloon, TrayIcon},
 };
 use crate::{
   error::WindowError,
@@ -81,12 +98,29 @@ use crate::{
     get_window_ex_style,
     get_window_style,
     hwnd_dpi,
-    is_dark_mode_supported,
-    is_system_dark_mode_enabled,
+    is_frame_customization_supported,
+    is_system_backdrop_supported,
+    set_dwm_attribute,
+    set_frame_extended,
     Monitor,
   },
   window::{
-    data::{Flow, Internal, PhysicalPosition, Size, SyncData, Theme, Visibility},
+    data::{
+      Backdrop,
+      Color,
+      CornerPreference,
+      DpiPolicy,
+      Flow,
+      Internal,
+      PhysicalPosition,
+      RedrawPacing,
+      Size,
+      SyncData,
+      Theme,
+      UserAttention,
+      Visibility,
+      WindowLevel,
+    },
     frame::Style,
     input::Input,
     message::Message,
@@ -95,16 +129,26 @@ use crate::{
   },
 };
 
+mod background;
+pub mod clipboard;
 mod command;
 pub mod cursor;
 pub mod data;
+mod drop_target;
 pub mod frame;
+pub mod hotkey;
+pub mod icon;
+pub mod inhibit;
 pub mod input;
+pub mod menu;
 pub mod message;
 pub mod monitor;
+pub mod present;
 pub mod procedure;
 pub mod settings;
 pub mod stage;
+pub mod suppress;
+pub mod tray;
 
 /// Main window class. Uses internal mutability. Window is destroyed on drop. Cloning does not create a new window,
 /// but instead clones the smart pointer handle to the same window.
@@ -129,6 +173,7 @@ impl Window {
     title: impl Into<String>,
     size: impl Into<Size>,
     position: impl Into<Option<Position>>,
+    parent: Option<Parent>,
     settings: WindowSettings,
   ) -> Result<Self, WindowError> {
     let title: String = title.into();
@@ -143,20 +188,32 @@ impl Window {
       skip_wait: Arc::new(Mutex::new(true)),
     };
 
+    let is_child = matches!(parent, Some(Parent::Child(_)));
+
     let create_info = CreateInfo {
       title: title.clone(),
       size,
       position,
+      parent,
       settings: settings.clone(),
       class_atom: 0,
       window: None,
-      message: Arc::new(Mutex::new(None)),
+      message: Arc::new(Mutex::new(VecDeque::new())),
       sync: sync.clone(),
       style: Style {
         visibility: settings.visibility,
         decorations: settings.decorations,
         fullscreen: settings.fullscreen,
         resizeable: settings.resizeable,
+        transparent: settings.transparent,
+        opacity: settings.opacity,
+        cursor_hittest: settings.cursor_hittest,
+        level: settings.window_level,
+        skip_taskbar: settings.skip_taskbar,
+        tool_window: settings.tool_window,
+        child: is_child,
+        popup: settings.popup,
+        // applied when the window is first shown
         minimized: false,
         maximized: false,
         active: false,
@@ -189,6 +246,8 @@ impl Window {
       .name("window".to_owned())
       .spawn(move || -> Result<(), WindowError> {
         let title = create_info.title.clone();
+        // OLE is used for taskbar integration and drag-and-drop
+        unsafe { OleInitialize(None) }?;
         // let flow = create_info.settings.flow;
         let window = Self::create_hwnd(create_info)?;
 
@@ -197,6 +256,7 @@ impl Window {
 
         tracing::trace!("[`{}`]: pumping messages", title);
         while Self::message_pump() {}
+        unsafe { OleUninitialize() };
 
         tracing::trace!("[`{}`]: joining main thread", title);
         Ok(())
@@ -236,13 +296,28 @@ impl Window {
 
     tracing::trace!("[`{}`]: creating window handle", &create_info.title);
 
-    if unsafe {
-      SetProcessDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2)
-    }
-    .is_err()
-    {
-      unsafe { SetProcessDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE) }
-        .unwrap();
+    let parent_hwnd = create_info
+      .parent
+      .as_ref()
+      .map(|parent| parent.window().0.hwnd);
+
+    let awareness_contexts: &[DPI_AWARENESS_CONTEXT] =
+      match create_info.settings.dpi_policy {
+        DpiPolicy::PerMonitorV2 => &[
+          DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
+          DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE,
+        ],
+        DpiPolicy::PerMonitor => &[DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE],
+        DpiPolicy::System => &[DPI_AWARENESS_CONTEXT_SYSTEM_AWARE],
+        DpiPolicy::Unaware => &[DPI_AWARENESS_CONTEXT_UNAWARE],
+        DpiPolicy::Unchanged => &[],
+      };
+    // fails for every window after the first, as the awareness can only be set once
+    let awareness_set = awareness_contexts
+      .iter()
+      .any(|&context| unsafe { SetProcessDpiAwarenessContext(context) }.is_ok());
+    if !awareness_contexts.is_empty() && !awareness_set {
+      tracing::trace!("[`{}`]: process DPI awareness already set", &create_info.title);
     }
 
     let hwnd = unsafe {
@@ -255,7 +330,7 @@ impl Window {
         WindowsAndMessaging::CW_USEDEFAULT,
         WindowsAndMessaging::CW_USEDEFAULT,
         WindowsAndMessaging::CW_USEDEFAULT,
-        None,
+        parent_hwnd.unwrap_or_default(),
         None,
         hinstance,
         Some(std::ptr::addr_of_mut!(create_info) as _),
@@ -276,6 +351,10 @@ impl Window {
   fn message_pump() -> bool {
     let mut msg = MSG::default();
     if unsafe { GetMessageW(&mut msg, None, 0, 0) }.as_bool() {
+      // accelerated keys are sent as `WM_COMMAND` instead of being dispatched
+      if menu::translate_accelerator(&msg) {
+        return true;
+      }
       unsafe {
         TranslateMessage(&msg);
         DispatchMessageW(&msg);
@@ -289,7 +368,7 @@ impl Window {
   fn take_message(&self) -> Option<Message> {
     let flow = self.0.data.lock().unwrap().flow;
     if let Flow::Wait = flow {
-      let should_wait = self.0.message.lock().unwrap().is_none();
+      let should_wait = self.0.message.lock().unwrap().is_empty();
       if should_wait {
         let (lock, cvar) = self.0.sync.new_message.as_ref();
         let mut new = cvar.wait_while(lock.lock().unwrap(), |new| !*new).unwrap();
@@ -302,7 +381,7 @@ impl Window {
       .message
       .lock()
       .unwrap()
-      .take()
+      .pop_front()
       .or(Some(Message::Loop(LoopMessage::Empty)))
   }
 
@@ -358,14 +437,68 @@ impl Window {
     self.0.data.lock().unwrap().style.visibility
   }
 
+  /// Whether the window is shown, not minimized, and on the current virtual desktop.
+  /// Changes are sent as [`Message::Occluded`].
+  pub fn is_visible_on_screen(&self) -> bool {
+    !self.0.data.lock().unwrap().occluded
+  }
+
   pub fn theme(&self) -> Theme {
     self.0.data.lock().unwrap().theme
   }
 
+  /// Accent color chosen in the personalization settings. Changes are sent as
+  /// [`Message::AccentColorChanged`].
+  pub fn system_accent_color(&self) -> Option<Color> {
+    self.0.data.lock().unwrap().accent_color
+  }
+
+  /// Changes are sent as [`Message::HighContrastChanged`].
+  pub fn is_high_contrast(&self) -> bool {
+    self.0.data.lock().unwrap().high_contrast
+  }
+
+  pub fn backdrop(&self) -> Backdrop {
+    self.0.data.lock().unwrap().backdrop
+  }
+
+  pub fn corner_preference(&self) -> CornerPreference {
+    self.0.data.lock().unwrap().corner_preference
+  }
+
+  pub fn border_color(&self) -> Option<Color> {
+    self.0.data.lock().unwrap().border_color
+  }
+
+  pub fn caption_color(&self) -> Option<Color> {
+    self.0.data.lock().unwrap().caption_color
+  }
+
+  pub fn caption_text_color(&self) -> Option<Color> {
+    self.0.data.lock().unwrap().caption_text_color
+  }
+
   pub fn flow(&self) -> Flow {
     self.0.data.lock().unwrap().flow
   }
 
+  /// Keyboard layout and input language currently used by the window.
+  pub fn keyboard_layout(&self) -> KeyboardLayout {
+    self.0.data.lock().unwrap().keyboard_layout.clone()
+  }
+
+  pub fn key_suppression(&self) -> KeySuppression {
+    self.0.data.lock().unwrap().key_suppression
+  }
+
+  pub fn mouse_capture(&self) -> bool {
+    self.0.data.lock().unwrap().mouse_capture
+  }
+
+  pub fn redraw_pacing(&self) -> RedrawPacing {
+    self.0.data.lock().unwrap().redraw_pacing
+  }
+
   pub fn title(&self) -> String {
     self.0.data.lock().unwrap().title.to_string()
   }
@@ -410,6 +543,48 @@ impl Window {
     }
   }
 
+  pub fn is_transparent(&self) -> bool {
+    self.0.data.lock().unwrap().style.transparent
+  }
+
+  pub fn opacity(&self) -> f32 {
+    self.0.data.lock().unwrap().style.opacity
+  }
+
+  pub fn cursor_hittest(&self) -> bool {
+    self.0.data.lock().unwrap().style.cursor_hittest
+  }
+
+  pub fn window_level(&self) -> WindowLevel {
+    self.0.data.lock().unwrap().style.level
+  }
+
+  pub fn skip_taskbar(&self) -> bool {
+    self.0.data.lock().unwrap().style.skip_taskbar
+  }
+
+  pub fn is_tool_window(&self) -> bool {
+    self.0.data.lock().unwrap().style.tool_window
+  }
+
+  /// The owner or parent the window was built with.
+  pub fn owner(&self) -> Option<Window> {
+    self.0.owner.clone()
+  }
+
+  pub fn is_child(&self) -> bool {
+    self.0.data.lock().unwrap().style.child
+  }
+
+  pub fn is_popup(&self) -> bool {
+    self.0.data.lock().unwrap().style.popup
+  }
+
+  /// Returns `true` while the window is disabling its owner.
+  pub fn is_modal(&self) -> bool {
+    self.0.data.lock().unwrap().modal
+  }
+
   pub fn fullscreen(&self) -> Option<Fullscreen> {
     self.0.data.lock().unwrap().style.fullscreen
   }
@@ -498,16 +673,137 @@ impl Window {
     self.0.data.lock().unwrap().style.maximized
   }
 
+  /// Handle to the system clipboard.
+  pub fn clipboard(&self) -> Clipboard {
+    Clipboard::new()
+  }
+
+  /// The menu bar of the window.
+  pub fn menu(&self) -> Option<Menu> {
+    self.0.data.lock().unwrap().menu.clone()
+  }
+
+  /// The tray icon of the window.
+  pub fn tray_icon(&self) -> Option<TrayIcon> {
+    self
+      .0
+      .data
+      .lock()
+      .unwrap()
+      .tray
+      .as_ref()
+      .map(|tray| tray.settings.clone())
+  }
+
   // SETTERS
 
-  fn force_set_cursor_icon(&self, cursor_icon: CursorIcon) {
+  /// Replaces the menu bar of the window. Selected items are sent as
+  /// [`Message::MenuCommand`].
+  pub fn set_menu(&self, menu: Option<Menu>) {
+    if menu == self.menu() {
+      return;
+    }
+    Command::SetMenu(menu).post(self.0.hwnd);
+  }
+
+  pub fn set_menu_item_enabled(&self, id: MenuId, enabled: bool) {
+    Command::SetMenuItemEnabled(id, enabled).post(self.0.hwnd);
+  }
+
+  pub fn set_menu_item_checked(&self, id: MenuId, checked: bool) {
+    Command::SetMenuItemChecked(id, checked).post(self.0.hwnd);
+  }
+
+  /// Shows a context menu at the position relative to the client area. The selected item
+  /// is sent as [`Message::MenuCommand`].
+  pub fn show_context_menu(&self, position: impl Into<Position>, menu: &Menu) {
+    Command::ShowContextMenu(position.into(), menu.clone()).post(self.0.hwnd);
+  }
+
+  /// Adds, updates, or removes the tray icon of the window. Events are sent as
+  /// [`Message::Tray`].
+  pub fn set_tray_icon(&self, tray_icon: Option<TrayIcon>) {
+    Command::SetTrayIcon(tray_icon).post(self.0.hwnd);
+  }
+
+  /// Shows a notification from the tray icon. Does nothing without a tray icon.
+  pub fn show_balloon(&self, balloon: Balloon) {
+    Command::ShowBalloon(balloon).post(self.0.hwnd);
+  }
+
+  /// Starts a timer which sends [`Message::Timer`] once the duration elapses, and keeps
+  /// doing so until killed if `repeat` is set. The resolution is roughly 10 milliseconds.
+  pub fn set_timer(&self, duration: Duration, repeat: bool) -> TimerId {
+    let id = {
+      let mut data = self.0.data.lock().unwrap();
+      let id = TimerId(data.next_timer_id);
+      data.next_timer_id += 1;
+      id
+    };
+    Command::SetTimer(id, duration, repeat).post(self.0.hwnd);
+    id
+  }
+
+  pub fn kill_timer(&self, id: TimerId) {
+    Command::KillTimer(id).post(self.0.hwnd);
+  }
+
+  /// Keeps the display or system awake until the returned [`Inhibitor`] is dropped or the
+  /// window closes.
+  pub fn inhibit(&self, flags: InhibitFlags) -> Inhibitor {
+    let id = {
+      let mut data = self.0.data.lock().unwrap();
+      let id = data.next_inhibitor_id;
+      data.next_inhibitor_id += 1;
+      id
+    };
+    Command::Inhibit(id, flags).post(self.0.hwnd);
+    Inhibitor::new(Arc::downgrade(&self.0), id)
+  }
+
+  /// Registers a system-wide hotkey, sent as [`Message::Hotkey`](message::Message::Hotkey)
+  /// until the returned [`HotkeyRegistration`] is dropped or the window closes. Fails if
+  /// another application already uses the key combination.
+  pub fn register_hotkey(
+    &self,
+    hotkey: Hotkey,
+  ) -> Result<HotkeyRegistration, WindowError> {
+    let id = hotkey.register(self.0.hwnd)?;
+    self.0.data.lock().unwrap().hotkeys.insert(id, hotkey);
+    Ok(HotkeyRegistration::new(Arc::downgrade(&self.0), id))
+  }
+
+  /// Minimize the window, or restore it to its previous state.
+  pub fn set_minimized(&self, minimized: bool) {
+    if minimized == self.is_minimized() {
+      return;
+    }
+    Command::SetMinimized(minimized).post(self.0.hwnd);
+  }
+
+  /// Maximize the window, or restore it to its normal size.
+  pub fn set_maximized(&self, maximized: bool) {
+    if maximized == self.is_maximized() {
+      return;
+    }
+    Command::SetMaximized(maximized).post(self.0.hwnd);
+  }
+
+  /// Restore the window from being minimized or maximized.
+  pub fn restore(&self) {
+    Command::Restore.post(self.0.hwnd);
+  }
+
+  fn force_set_cursor_icon(&self, cursor_icon: CursorImage) {
     // self.state.write_lock().position = position;
     Command::SetCursorIcon(cursor_icon).post(self.0.hwnd);
   }
 
-  pub fn set_cursor_icon(&self, cursor_icon: CursorIcon) {
-    let selected_icon = self.0.data.lock().unwrap().cursor.selected_icon;
-    if selected_icon == cursor_icon {
+  /// Sets the cursor shown in the client area to a system
+  /// [`CursorIcon`](cursor_icon::CursorIcon) or a [`CustomCursor`](cursor::CustomCursor).
+  pub fn set_cursor_icon(&self, cursor_icon: impl Into<CursorImage>) {
+    let cursor_icon = cursor_icon.into();
+    if self.0.data.lock().unwrap().cursor.selected_icon == cursor_icon {
       return;
     }
     self.force_set_cursor_icon(cursor_icon)
@@ -554,7 +850,7 @@ impl Window {
       AdjustWindowRectExForDpi(
         &mut window_rect,
         get_window_style(&style),
-        false,
+        self.0.data.lock().unwrap().menu.is_some(),
         get_window_ex_style(&style),
         hwnd_dpi(self.0.hwnd),
       )
@@ -578,6 +874,21 @@ impl Window {
     self.force_set_inner_size(size)
   }
 
+  /// Replaces the inner size the window is resized to once [`Message::ScaleFactorChanged`]
+  /// has been handled, such as to keep the physical size. Has no effect unless called while
+  /// handling the message.
+  pub fn set_scale_factor_inner_size(&self, size: impl Into<Size>) {
+    let mut data = self.0.data.lock().unwrap();
+    let physical_size = size.into().as_physical(data.scale_factor);
+    match &mut data.scale_factor_inner_size {
+      Some(inner_size) => *inner_size = physical_size,
+      None => tracing::warn!(
+        "[`{}`]: scale factor inner size set outside of `Message::ScaleFactorChanged`",
+        data.title
+      ),
+    }
+  }
+
   fn force_set_visibility(&self, visibility: Visibility) {
     self.0.data.lock().unwrap().style.visibility = visibility;
     Command::SetVisibility(visibility).post(self.0.hwnd);
@@ -603,36 +914,7 @@ impl Window {
   }
 
   fn force_set_theme(&self, theme: Theme) {
-    let theme = match theme {
-      Theme::Auto => {
-        if is_system_dark_mode_enabled() {
-          Theme::Dark
-        } else {
-          Theme::Light
-        }
-      }
-      Theme::Dark => {
-        if is_dark_mode_supported() {
-          Theme::Dark
-        } else {
-          Theme::Light
-        }
-      }
-      Theme::Light => Theme::Light,
-    };
-
-    self.0.data.lock().unwrap().theme = theme;
-    let dark_mode = BOOL::from(theme == Theme::Dark);
-    if let Err(_error) = unsafe {
-      DwmSetWindowAttribute(
-        self.0.hwnd,
-        Dwm::DWMWA_USE_IMMERSIVE_DARK_MODE,
-        std::ptr::addr_of!(dark_mode) as *const std::ffi::c_void,
-        std::mem::size_of::<BOOL>() as u32,
-      )
-    } {
-      tracing::error!("{_error}");
-    };
+    self.0.apply_theme(theme);
   }
 
   pub fn set_theme(&self, theme: Theme) {
@@ -642,6 +924,198 @@ impl Window {
     self.force_set_theme(theme)
   }
 
+  fn force_set_backdrop(&self, backdrop: Backdrop) {
+    self.0.data.lock().unwrap().backdrop = backdrop;
+    if is_system_backdrop_supported() {
+      let backdrop_type = match backdrop {
+        Backdrop::Auto => Dwm::DWMSBT_AUTO,
+        Backdrop::None => Dwm::DWMSBT_NONE,
+        Backdrop::Mica => Dwm::DWMSBT_MAINWINDOW,
+        Backdrop::Acrylic => Dwm::DWMSBT_TRANSIENTWINDOW,
+        Backdrop::Tabbed => Dwm::DWMSBT_TABBEDWINDOW,
+      };
+      set_dwm_attribute(self.0.hwnd, Dwm::DWMWA_SYSTEMBACKDROP_TYPE, &backdrop_type);
+      set_frame_extended(
+        self.0.hwnd,
+        !matches!(backdrop, Backdrop::Auto | Backdrop::None),
+      );
+    } else if is_frame_customization_supported() {
+      // Windows 11 builds before 22H2 only expose Mica through `DWMWA_MICA_EFFECT`
+      let mica = BOOL::from(backdrop == Backdrop::Mica);
+      set_dwm_attribute(self.0.hwnd, Dwm::DWMWINDOWATTRIBUTE(1029), &mica);
+      set_frame_extended(self.0.hwnd, backdrop == Backdrop::Mica);
+    } else if backdrop != Backdrop::Auto {
+      tracing::warn!("[`{}`]: system backdrops require Windows 11", self.title());
+    }
+  }
+
+  /// Set the material DWM draws behind the window. Falls back to Mica or no backdrop on
+  /// older versions of Windows.
+  pub fn set_backdrop(&self, backdrop: Backdrop) {
+    if backdrop == self.0.data.lock().unwrap().backdrop {
+      return;
+    }
+    self.force_set_backdrop(backdrop)
+  }
+
+  fn force_set_corner_preference(&self, corner_preference: CornerPreference) {
+    self.0.data.lock().unwrap().corner_preference = corner_preference;
+    if !is_frame_customization_supported() {
+      return;
+    }
+    let preference = match corner_preference {
+      CornerPreference::Default => Dwm::DWMWCP_DEFAULT,
+      CornerPreference::Round => Dwm::DWMWCP_ROUND,
+      CornerPreference::RoundSmall => Dwm::DWMWCP_ROUNDSMALL,
+      CornerPreference::Square => Dwm::DWMWCP_DONOTROUND,
+    };
+    set_dwm_attribute(self.0.hwnd, Dwm::DWMWA_WINDOW_CORNER_PREFERENCE, &preference);
+  }
+
+  pub fn set_corner_preference(&self, corner_preference: CornerPreference) {
+    if corner_preference == self.0.data.lock().unwrap().corner_preference {
+      return;
+    }
+    self.force_set_corner_preference(corner_preference)
+  }
+
+  fn set_frame_color(&self, attribute: Dwm::DWMWINDOWATTRIBUTE, color: Option<Color>) {
+    if !is_frame_customization_supported() {
+      return;
+    }
+    let colorref = color.map_or(Dwm::DWMWA_COLOR_DEFAULT, |color| color.as_colorref());
+    set_dwm_attribute(self.0.hwnd, attribute, &colorref);
+  }
+
+  fn force_set_border_color(&self, color: Option<Color>) {
+    self.0.data.lock().unwrap().border_color = color;
+    self.set_frame_color(Dwm::DWMWA_BORDER_COLOR, color);
+  }
+
+  /// Set the color of the window border. `None` restores the system default.
+  pub fn set_border_color(&self, color: Option<Color>) {
+    if color == self.0.data.lock().unwrap().border_color {
+      return;
+    }
+    self.force_set_border_color(color)
+  }
+
+  fn force_set_caption_color(&self, color: Option<Color>) {
+    self.0.data.lock().unwrap().caption_color = color;
+    self.set_frame_color(Dwm::DWMWA_CAPTION_COLOR, color);
+  }
+
+  /// Set the color of the title bar. `None` restores the system default.
+  pub fn set_caption_color(&self, color: Option<Color>) {
+    if color == self.0.data.lock().unwrap().caption_color {
+      return;
+    }
+    self.force_set_caption_color(color)
+  }
+
+  fn force_set_caption_text_color(&self, color: Option<Color>) {
+    self.0.data.lock().unwrap().caption_text_color = color;
+    self.set_frame_color(Dwm::DWMWA_TEXT_COLOR, color);
+  }
+
+  /// Set the color of the title bar text. `None` restores the system default.
+  pub fn set_caption_text_color(&self, color: Option<Color>) {
+    if color == self.0.data.lock().unwrap().caption_text_color {
+      return;
+    }
+    self.force_set_caption_text_color(color)
+  }
+
+  fn force_set_transparent(&self, transparent: bool) {
+    self.0.data.lock().unwrap().style.transparent = transparent;
+    Command::SetTransparent(transparent).post(self.0.hwnd);
+  }
+
+  /// Allow the client area to be composited with per-pixel alpha. Graphics surfaces should
+  /// be configured with a premultiplied alpha mode for this to have an effect.
+  pub fn set_transparent(&self, transparent: bool) {
+    if transparent == self.0.data.lock().unwrap().style.transparent {
+      return;
+    }
+    self.force_set_transparent(transparent)
+  }
+
+  fn force_set_opacity(&self, opacity: f32) {
+    self.0.data.lock().unwrap().style.opacity = opacity;
+    Command::SetOpacity(opacity).post(self.0.hwnd);
+  }
+
+  /// Set the opacity of the whole window, including decorations. Clamped to `0.0..=1.0`.
+  pub fn set_opacity(&self, opacity: f32) {
+    let opacity = opacity.clamp(0.0, 1.0);
+    if opacity == self.0.data.lock().unwrap().style.opacity {
+      return;
+    }
+    self.force_set_opacity(opacity)
+  }
+
+  fn force_set_cursor_hittest(&self, hittest: bool) {
+    self.0.data.lock().unwrap().style.cursor_hittest = hittest;
+    Command::SetCursorHittest(hittest).post(self.0.hwnd);
+  }
+
+  /// When `false`, mouse input passes through the window to whatever is beneath it.
+  pub fn set_cursor_hittest(&self, hittest: bool) {
+    if hittest == self.0.data.lock().unwrap().style.cursor_hittest {
+      return;
+    }
+    self.force_set_cursor_hittest(hittest)
+  }
+
+  fn force_set_window_level(&self, window_level: WindowLevel) {
+    self.0.data.lock().unwrap().style.level = window_level;
+    Command::SetWindowLevel(window_level).post(self.0.hwnd);
+  }
+
+  pub fn set_window_level(&self, window_level: WindowLevel) {
+    if window_level == self.0.data.lock().unwrap().style.level {
+      return;
+    }
+    self.force_set_window_level(window_level)
+  }
+
+  fn force_set_skip_taskbar(&self, skip_taskbar: bool) {
+    self.0.data.lock().unwrap().style.skip_taskbar = skip_taskbar;
+    Command::SetSkipTaskbar(skip_taskbar).post(self.0.hwnd);
+  }
+
+  /// Hide or show the taskbar button of the window.
+  pub fn set_skip_taskbar(&self, skip_taskbar: bool) {
+    if skip_taskbar == self.0.data.lock().unwrap().style.skip_taskbar {
+      return;
+    }
+    self.force_set_skip_taskbar(skip_taskbar)
+  }
+
+  fn force_set_tool_window(&self, tool_window: bool) {
+    self.0.data.lock().unwrap().style.tool_window = tool_window;
+    Command::SetToolWindow(tool_window).post(self.0.hwnd);
+  }
+
+  pub fn set_tool_window(&self, tool_window: bool) {
+    if tool_window == self.0.data.lock().unwrap().style.tool_window {
+      return;
+    }
+    self.force_set_tool_window(tool_window)
+  }
+
+  /// Bring the window to the foreground and give it keyboard focus, restoring it if
+  /// minimized. Windows may refuse to steal focus from another application, in which case
+  /// the taskbar button will flash instead.
+  pub fn focus(&self) {
+    Command::Focus.post(self.0.hwnd);
+  }
+
+  /// Flash the window until it is focused. `None` stops any flashing in progress.
+  pub fn request_user_attention(&self, attention: Option<UserAttention>) {
+    Command::RequestUserAttention(attention).post(self.0.hwnd);
+  }
+
   fn force_set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
     self.0.data.lock().unwrap().style.fullscreen = fullscreen;
     Command::SetFullscreen(fullscreen).post(self.0.hwnd);
@@ -696,6 +1170,11 @@ impl Window {
     self.force_set_cursor_visibility(cursor_visibility)
   }
 
+  /// Moves the cursor to the position relative to the client area.
+  pub fn set_cursor_position(&self, position: impl Into<Position>) {
+    Command::SetCursorPosition(position.into()).post(self.0.hwnd);
+  }
+
   fn force_set_subtitle(&self, subtitle: impl AsRef<str>) {
     self.0.data.lock().unwrap().subtitle = subtitle.as_ref().into();
     let title = HSTRING::from(format!(
@@ -714,17 +1193,65 @@ impl Window {
     self.force_set_subtitle(subtitle)
   }
 
-  fn force_request_redraw(&self) {
-    self.0.data.lock().unwrap().requested_redraw = true;
-    Command::Redraw.post(self.0.hwnd);
+  /// Applies while the window is focused and fullscreen.
+  pub fn set_key_suppression(&self, key_suppression: KeySuppression) {
+    if key_suppression == self.0.data.lock().unwrap().key_suppression {
+      return;
+    }
+    self.0.data.lock().unwrap().key_suppression = key_suppression;
+    Command::SetKeySuppression(key_suppression).post(self.0.hwnd);
   }
 
-  /// Request a new Draw event
+  /// Applies from the next button press. A capture that is already held is kept until the
+  /// buttons are released.
+  pub fn set_mouse_capture(&self, mouse_capture: bool) {
+    self.0.data.lock().unwrap().mouse_capture = mouse_capture;
+  }
+
+  pub fn set_redraw_pacing(&self, redraw_pacing: RedrawPacing) {
+    self.0.data.lock().unwrap().redraw_pacing = redraw_pacing;
+  }
+
+  /// Request a [`Message::RedrawRequested`]. Requests made before it is sent are coalesced
+  /// into one.
   pub fn request_redraw(&self) {
-    if self.0.data.lock().unwrap().requested_redraw {
+    // checked and set under one lock, so concurrent requests post only one command
+    if std::mem::replace(&mut self.0.data.lock().unwrap().requested_redraw, true) {
       return;
     }
-    self.force_request_redraw()
+    Command::Redraw.post(self.0.hwnd);
+  }
+
+  /// Presents CPU-rendered pixels, given as rows from top to bottom, scaled to the client
+  /// area. The frame is kept and drawn again whenever the window is repainted.
+  pub fn present(
+    &self,
+    pixels: &[u32],
+    size: PhysicalSize,
+    format: PixelFormat,
+    scaling: Scaling,
+  ) -> Result<(), WindowError> {
+    let expected_len = size.width as usize * size.height as usize;
+    if pixels.len() != expected_len {
+      return Err(WindowError::Error(format!(
+        "expected {expected_len} pixels for a {}x{} frame, found {}",
+        size.width,
+        size.height,
+        pixels.len()
+      )));
+    }
+
+    let client = self.inner_size();
+    // only the window thread repainting the previous frame waits on this lock
+    let mut framebuffer = self.0.framebuffer.lock().unwrap();
+    let framebuffer = framebuffer.get_or_insert_with(Framebuffer::default);
+    framebuffer.swap(pixels, size, format, scaling);
+
+    let hdc = unsafe { GetDC(self.0.hwnd) };
+    framebuffer.draw(hdc, client);
+    unsafe { ReleaseDC(self.0.hwnd, hdc) };
+
+    Ok(())
   }
 
   #[cfg(all(feature = "rwh_06", not(feature = "rwh_05")))]
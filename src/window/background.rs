@@ -0,0 +1,141 @@
+use std::sync::{mpsc, OnceLock};
+
+use windows::{
+  core::w,
+  Win32::{
+    Foundation::{HWND, LPARAM, LRESULT, WPARAM},
+    System::LibraryLoader::GetModuleHandleW,
+    UI::WindowsAndMessaging::{
+      self,
+      CreateWindowExW,
+      DefWindowProcW,
+      DispatchMessageW,
+      GetMessageW,
+      RegisterClassExW,
+      SendMessageW,
+      MSG,
+      WNDCLASSEXW,
+    },
+  },
+};
+
+use super::hotkey;
+use crate::error::WindowError;
+
+/// Runs a closure passed by [`call`]. The pointer to it is sent as `WPARAM`.
+const CALL_MESSAGE: u32 = WindowsAndMessaging::WM_USER + 1;
+
+/// Hidden message-only window, created on first use, whose thread does nothing but pump
+/// messages. Unlike window threads, which wait on the main thread between messages, it
+/// answers messages sent to it at any time, which makes it safe to hand to the system
+/// wherever it may send messages synchronously, such as when taking clipboard ownership.
+pub(crate) fn hwnd() -> Option<HWND> {
+  static BACKGROUND: OnceLock<Option<HWND>> = OnceLock::new();
+  *BACKGROUND.get_or_init(spawn)
+}
+
+/// Runs the closure on the background thread with the background window and waits for its
+/// result.
+pub(crate) fn call<R: Send>(f: impl FnOnce(HWND) -> R + Send) -> Result<R, WindowError> {
+  let hwnd = hwnd()
+    .ok_or_else(|| WindowError::Error("background window is unavailable".to_owned()))?;
+
+  let mut f = Some(f);
+  let mut result = None;
+  let mut run = |hwnd: HWND| {
+    if let Some(f) = f.take() {
+      result = Some(f(hwnd));
+    }
+  };
+  let mut run: &mut dyn FnMut(HWND) = &mut run;
+  let run_ptr = &mut run as *mut &mut dyn FnMut(HWND);
+  unsafe { SendMessageW(hwnd, CALL_MESSAGE, WPARAM(run_ptr as usize), LPARAM(0)) };
+
+  result.ok_or_else(|| {
+    WindowError::Error("background window did not run the call".to_owned())
+  })
+}
+
+fn spawn() -> Option<HWND> {
+  let (sender, receiver) = mpsc::channel();
+  let handle = std::thread::Builder::new()
+    .name("witer background".to_owned())
+    .spawn(move || {
+      let hwnd = match create_hwnd() {
+        Ok(hwnd) => hwnd,
+        Err(e) => {
+          tracing::error!("{e}");
+          let _ = sender.send(None);
+          return;
+        }
+      };
+      let _ = sender.send(Some(hwnd));
+
+      let mut msg = MSG::default();
+      while unsafe { GetMessageW(&mut msg, None, 0, 0) }.0 > 0 {
+        unsafe { DispatchMessageW(&msg) };
+      }
+    });
+
+  if let Err(e) = handle {
+    tracing::error!("{e}");
+    return None;
+  }
+  receiver.recv().ok().flatten()
+}
+
+fn create_hwnd() -> windows::core::Result<HWND> {
+  let hinstance = unsafe { GetModuleHandleW(None) }?;
+  let wc = WNDCLASSEXW {
+    cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+    lpfnWndProc: Some(background_proc),
+    hInstance: hinstance.into(),
+    lpszClassName: w!("witer background"),
+    ..Default::default()
+  };
+  if unsafe { RegisterClassExW(&wc) } == 0 {
+    return Err(windows::core::Error::from_win32());
+  }
+
+  let hwnd = unsafe {
+    CreateWindowExW(
+      Default::default(),
+      w!("witer background"),
+      None,
+      Default::default(),
+      0,
+      0,
+      0,
+      0,
+      WindowsAndMessaging::HWND_MESSAGE,
+      None,
+      hinstance,
+      None,
+    )
+  };
+  if hwnd.0 == 0 {
+    return Err(windows::core::Error::from_win32());
+  }
+  Ok(hwnd)
+}
+
+extern "system" fn background_proc(
+  hwnd: HWND,
+  msg: u32,
+  wparam: WPARAM,
+  lparam: LPARAM,
+) -> LRESULT {
+  match msg {
+    CALL_MESSAGE => {
+      // the sender waits for the message to be handled, so the closure is still alive
+      let run = unsafe { &mut *(wparam.0 as *mut &mut dyn FnMut(HWND)) };
+      run(hwnd);
+      LRESULT(0)
+    }
+    WindowsAndMessaging::WM_HOTKEY => {
+      hotkey::forward(wparam, lparam);
+      LRESULT(0)
+    }
+    _ => unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) },
+  }
+}
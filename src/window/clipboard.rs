@@ -0,0 +1,406 @@
+use std::{path::PathBuf, thread, time::Duration};
+
+use windows::Win32::{
+  Foundation::{GlobalFree, HANDLE, HGLOBAL},
+  Graphics::Gdi::{BITMAPINFOHEADER, BITMAPV5HEADER, BI_BITFIELDS, BI_RGB},
+  System::{
+    DataExchange::{
+      CloseClipboard,
+      EmptyClipboard,
+      GetClipboardData,
+      IsClipboardFormatAvailable,
+      OpenClipboard,
+      SetClipboardData,
+    },
+    Memory::{GlobalAlloc, GlobalLock, GlobalSize, GlobalUnlock, GMEM_MOVEABLE},
+    Ole::{CF_DIB, CF_DIBV5, CF_HDROP, CF_UNICODETEXT},
+  },
+  UI::Shell::HDROP,
+};
+
+use super::background;
+use crate::{
+  error::WindowError,
+  utilities::{hdrop_paths, read_wide_string},
+};
+
+/// `LCS_sRGB` color space tag.
+const LCS_SRGB: u32 = 0x7352_4742;
+
+/// Image held on the clipboard, stored as tightly packed 8-bit RGBA rows from top to
+/// bottom.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClipboardImage {
+  pub width: u32,
+  pub height: u32,
+  pub pixels: Vec<u8>,
+}
+
+/// Handle to the system clipboard. Obtained with [`Window::clipboard`](super::Window::clipboard)
+/// or [`Clipboard::new`].
+///
+/// Contents set through any handle are owned by a hidden window on a background thread, as
+/// the system notifies the previous owner synchronously while the clipboard is emptied and
+/// window threads can be blocked waiting on the main thread.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Clipboard {
+  _private: (),
+}
+
+impl Clipboard {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Fetches text from the clipboard.
+  pub fn text(&self) -> Option<String> {
+    let _guard = self.open().ok()?;
+    let handle = get_data(CF_UNICODETEXT.0)?;
+    Some(unsafe { read_wide_string(HGLOBAL(handle.0 as _)) })
+  }
+
+  /// Replaces the contents of the clipboard with the text.
+  pub fn set_text(&self, text: &str) -> Result<(), WindowError> {
+    let wide: Vec<u16> = text.encode_utf16().chain(Some(0)).collect();
+    let bytes =
+      unsafe { std::slice::from_raw_parts(wide.as_ptr() as *const u8, wide.len() * 2) };
+
+    let _guard = self.open()?;
+    unsafe { EmptyClipboard() }?;
+    set_data(CF_UNICODETEXT.0, &[bytes])
+  }
+
+  /// Fetches an image from the clipboard.
+  pub fn image(&self) -> Option<ClipboardImage> {
+    let _guard = self.open().ok()?;
+    let handle = get_data(CF_DIB.0)?;
+    let hglobal = HGLOBAL(handle.0 as _);
+
+    let ptr = unsafe { GlobalLock(hglobal) } as *const u8;
+    if ptr.is_null() {
+      return None;
+    }
+    let size = unsafe { GlobalSize(hglobal) };
+    let image = decode_dib(unsafe { std::slice::from_raw_parts(ptr, size) });
+    let _ = unsafe { GlobalUnlock(hglobal) };
+
+    image
+  }
+
+  /// Replaces the contents of the clipboard with the image.
+  pub fn set_image(&self, image: &ClipboardImage) -> Result<(), WindowError> {
+    let expected_len = image.width as usize * image.height as usize * 4;
+    if image.pixels.len() != expected_len {
+      return Err(WindowError::Error(format!(
+        "expected {expected_len} bytes of RGBA pixels, found {}",
+        image.pixels.len()
+      )));
+    }
+
+    let header = BITMAPV5HEADER {
+      bV5Size: std::mem::size_of::<BITMAPV5HEADER>() as u32,
+      bV5Width: image.width as i32,
+      // bottom-up, as some applications ignore top-down bitmaps
+      bV5Height: image.height as i32,
+      bV5Planes: 1,
+      bV5BitCount: 32,
+      bV5Compression: BI_BITFIELDS,
+      bV5SizeImage: expected_len as u32,
+      bV5RedMask: 0x00ff_0000,
+      bV5GreenMask: 0x0000_ff00,
+      bV5BlueMask: 0x0000_00ff,
+      bV5AlphaMask: 0xff00_0000,
+      bV5CSType: LCS_SRGB,
+      ..Default::default()
+    };
+    let header = unsafe {
+      std::slice::from_raw_parts(
+        &header as *const BITMAPV5HEADER as *const u8,
+        std::mem::size_of::<BITMAPV5HEADER>(),
+      )
+    };
+
+    let row_len = image.width as usize * 4;
+    let pixels: Vec<u8> = image
+      .pixels
+      .chunks_exact(row_len.max(1))
+      .rev()
+      .flat_map(|row| row.chunks_exact(4))
+      .flat_map(|pixel| [pixel[2], pixel[1], pixel[0], pixel[3]])
+      .collect();
+
+    let _guard = self.open()?;
+    unsafe { EmptyClipboard() }?;
+    set_data(CF_DIBV5.0, &[header, &pixels])
+  }
+
+  /// Fetches the list of files copied to the clipboard, such as from Explorer.
+  pub fn files(&self) -> Option<Vec<PathBuf>> {
+    let _guard = self.open().ok()?;
+    let handle = get_data(CF_HDROP.0)?;
+    Some(hdrop_paths(HDROP(handle.0)))
+  }
+
+  /// Opens the clipboard, retrying briefly if another application is holding it.
+  fn open(&self) -> Result<ClipboardGuard, WindowError> {
+    let mut attempts = 0;
+    loop {
+      match unsafe { OpenClipboard(background::hwnd().unwrap_or_default()) } {
+        Ok(()) => return Ok(ClipboardGuard),
+        Err(e) if attempts >= 10 => return Err(e.into()),
+        Err(_) => {
+          attempts += 1;
+          thread::sleep(Duration::from_millis(5));
+        }
+      }
+    }
+  }
+}
+
+/// Closes the clipboard on drop.
+struct ClipboardGuard;
+
+impl Drop for ClipboardGuard {
+  fn drop(&mut self) {
+    let _ = unsafe { CloseClipboard() };
+  }
+}
+
+fn get_data(format: u16) -> Option<HANDLE> {
+  unsafe { IsClipboardFormatAvailable(format as u32) }.ok()?;
+  unsafe { GetClipboardData(format as u32) }.ok()
+}
+
+/// Copies the concatenated parts into global memory and hands it to the clipboard.
+fn set_data(format: u16, parts: &[&[u8]]) -> Result<(), WindowError> {
+  let len = parts.iter().map(|part| part.len()).sum();
+  let hglobal = unsafe { GlobalAlloc(GMEM_MOVEABLE, len) }?;
+
+  let ptr = unsafe { GlobalLock(hglobal) } as *mut u8;
+  if ptr.is_null() {
+    let _ = unsafe { GlobalFree(hglobal) };
+    return Err(windows::core::Error::from_win32().into());
+  }
+  let mut offset = 0;
+  for part in parts {
+    unsafe { std::ptr::copy_nonoverlapping(part.as_ptr(), ptr.add(offset), part.len()) };
+    offset += part.len();
+  }
+  let _ = unsafe { GlobalUnlock(hglobal) };
+
+  // the system owns the memory once it is set
+  if let Err(e) = unsafe { SetClipboardData(format as u32, HANDLE(hglobal.0 as isize)) } {
+    let _ = unsafe { GlobalFree(hglobal) };
+    return Err(e.into());
+  }
+
+  Ok(())
+}
+
+/// Decodes an uncompressed 24 or 32-bit device-independent bitmap into RGBA.
+fn decode_dib(dib: &[u8]) -> Option<ClipboardImage> {
+  if dib.len() < std::mem::size_of::<BITMAPINFOHEADER>() {
+    return None;
+  }
+  let header = unsafe { (dib.as_ptr() as *const BITMAPINFOHEADER).read_unaligned() };
+
+  let width = header.biWidth.unsigned_abs();
+  let height = header.biHeight.unsigned_abs();
+  let bottom_up = header.biHeight > 0;
+  let bytes_per_pixel = match header.biBitCount {
+    24 | 32 => header.biBitCount as usize / 8,
+    _ => return None,
+  };
+
+  let mut offset = header.biSize as usize;
+  let masks = match header.biCompression {
+    c if c == BI_RGB.0 => None,
+    c if c == BI_BITFIELDS.0 && bytes_per_pixel == 4 => {
+      // masks follow a plain info header, and are part of larger headers
+      let masks_offset = std::mem::size_of::<BITMAPINFOHEADER>();
+      if header.biSize as usize == masks_offset {
+        offset = offset.checked_add(12)?;
+      }
+      let mask = |i: usize| {
+        let start = masks_offset + i * 4;
+        dib
+          .get(start..start + 4)
+          .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+      };
+      Some([mask(0)?, mask(1)?, mask(2)?])
+    }
+    _ => return None,
+  };
+  offset = offset.checked_add((header.biClrUsed as usize).checked_mul(4)?)?;
+
+  // checked, as the sizes come from the header and may not match the data
+  let stride = (width as usize)
+    .checked_mul(bytes_per_pixel)?
+    .checked_next_multiple_of(4)?;
+  let len = stride.checked_mul(height as usize)?;
+  let data = dib.get(offset..offset.checked_add(len)?)?;
+
+  let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+  let mut has_alpha = false;
+  for y in 0..height as usize {
+    let row = if bottom_up {
+      height as usize - 1 - y
+    } else {
+      y
+    };
+    let row = &data[row * stride..][..width as usize * bytes_per_pixel];
+    for pixel in row.chunks_exact(bytes_per_pixel) {
+      let [r, g, b, a] = match masks {
+        Some([r_mask, g_mask, b_mask]) => {
+          let value = u32::from_le_bytes(pixel.try_into().unwrap());
+          let channel = |mask: u32| {
+            (value & mask)
+              .checked_shr(mask.trailing_zeros())
+              .unwrap_or(0) as u8
+          };
+          [channel(r_mask), channel(g_mask), channel(b_mask), pixel[3]]
+        }
+        None => [pixel[2], pixel[1], pixel[0], *pixel.get(3).unwrap_or(&0)],
+      };
+      has_alpha |= bytes_per_pixel == 4 && a != 0;
+      pixels.extend_from_slice(&[r, g, b, a]);
+    }
+  }
+
+  // the alpha channel of most bitmaps is unused and left empty
+  if !has_alpha {
+    pixels
+      .chunks_exact_mut(4)
+      .for_each(|pixel| pixel[3] = u8::MAX);
+  }
+
+  Some(ClipboardImage {
+    width,
+    height,
+    pixels,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const OPAQUE: u8 = u8::MAX;
+
+  fn header(
+    width: i32,
+    height: i32,
+    bit_count: u16,
+    compression: u32,
+  ) -> BITMAPINFOHEADER {
+    BITMAPINFOHEADER {
+      biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+      biWidth: width,
+      biHeight: height,
+      biPlanes: 1,
+      biBitCount: bit_count,
+      biCompression: compression,
+      ..Default::default()
+    }
+  }
+
+  fn dib(header: BITMAPINFOHEADER, rest: &[u8]) -> Vec<u8> {
+    let header = unsafe {
+      std::slice::from_raw_parts(
+        &header as *const BITMAPINFOHEADER as *const u8,
+        std::mem::size_of::<BITMAPINFOHEADER>(),
+      )
+    };
+    [header, rest].concat()
+  }
+
+  fn masks(r: u32, g: u32, b: u32) -> Vec<u8> {
+    [r, g, b]
+      .iter()
+      .flat_map(|mask| mask.to_le_bytes())
+      .collect()
+  }
+
+  #[test]
+  fn bottom_up_24_bit() {
+    // rows are padded to 4 bytes and stored from the bottom up, as BGR
+    let rows = [[0x30, 0x20, 0x10, 0x60, 0x50, 0x40, 0, 0], [
+      0x03, 0x02, 0x01, 0x06, 0x05, 0x04, 0, 0,
+    ]]
+    .concat();
+    let image = decode_dib(&dib(header(2, 2, 24, BI_RGB.0), &rows)).unwrap();
+
+    assert_eq!((image.width, image.height), (2, 2));
+    assert_eq!(image.pixels, [
+      0x01, 0x02, 0x03, OPAQUE, 0x04, 0x05, 0x06, OPAQUE, //
+      0x10, 0x20, 0x30, OPAQUE, 0x40, 0x50, 0x60, OPAQUE,
+    ]);
+  }
+
+  #[test]
+  fn top_down_32_bit() {
+    let rows = [[0x03, 0x02, 0x01, 0x80], [0x06, 0x05, 0x04, 0x00]].concat();
+    let image = decode_dib(&dib(header(1, -2, 32, BI_RGB.0), &rows)).unwrap();
+
+    assert_eq!((image.width, image.height), (1, 2));
+    assert_eq!(image.pixels, [0x01, 0x02, 0x03, 0x80, 0x04, 0x05, 0x06, 0x00]);
+  }
+
+  #[test]
+  fn empty_alpha_is_opaque() {
+    let rows = [0x03, 0x02, 0x01, 0x00];
+    let image = decode_dib(&dib(header(1, 1, 32, BI_RGB.0), &rows)).unwrap();
+    assert_eq!(image.pixels, [0x01, 0x02, 0x03, OPAQUE]);
+  }
+
+  #[test]
+  fn bitfields_after_info_header() {
+    // RGBA byte order instead of the usual BGRA
+    let rest = [masks(0x0000_00ff, 0x0000_ff00, 0x00ff_0000), vec![
+      0x01, 0x02, 0x03, 0x80,
+    ]]
+    .concat();
+    let image = decode_dib(&dib(header(1, 1, 32, BI_BITFIELDS.0), &rest)).unwrap();
+    assert_eq!(image.pixels, [0x01, 0x02, 0x03, 0x80]);
+  }
+
+  #[test]
+  fn bitfields_in_v5_header() {
+    let v5_size = std::mem::size_of::<BITMAPV5HEADER>();
+    let mut header = header(1, 1, 32, BI_BITFIELDS.0);
+    header.biSize = v5_size as u32;
+
+    let info_size = std::mem::size_of::<BITMAPINFOHEADER>();
+    let mut rest = masks(0x00ff_0000, 0x0000_ff00, 0x0000_00ff);
+    rest.resize(v5_size - info_size, 0);
+    rest.extend_from_slice(&[0x03, 0x02, 0x01, 0x80]);
+
+    let image = decode_dib(&dib(header, &rest)).unwrap();
+    assert_eq!(image.pixels, [0x01, 0x02, 0x03, 0x80]);
+  }
+
+  #[test]
+  fn unsupported_bit_count() {
+    let rows = [0; 4];
+    assert_eq!(decode_dib(&dib(header(1, 1, 16, BI_RGB.0), &rows)), None);
+    assert_eq!(decode_dib(&dib(header(1, 1, 24, BI_BITFIELDS.0), &rows)), None);
+  }
+
+  #[test]
+  fn truncated() {
+    let rows = [0; 16];
+    let full = dib(header(2, 2, 32, BI_RGB.0), &rows);
+    assert!(decode_dib(&full).is_some());
+    assert_eq!(decode_dib(&full[..full.len() - 1]), None);
+    assert_eq!(decode_dib(&full[..10]), None);
+    assert_eq!(decode_dib(&[]), None);
+
+    // masks are missing
+    assert_eq!(decode_dib(&dib(header(1, 1, 32, BI_BITFIELDS.0), &[0; 8])), None);
+    // sizes far larger than the data
+    assert_eq!(decode_dib(&dib(header(i32::MAX, i32::MIN, 32, BI_RGB.0), &rows)), None);
+    let mut header = header(1, 1, 32, BI_RGB.0);
+    header.biClrUsed = u32::MAX;
+    assert_eq!(decode_dib(&dib(header, &rows)), None);
+  }
+}
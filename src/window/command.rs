@@ -1,4 +1,5 @@
-use cursor_icon::CursorIcon;
+use std::time::Duration;
+
 use windows::{
   core::HSTRING,
   Win32::{
@@ -7,7 +8,23 @@ use windows::{
   },
 };
 
-use super::data::{CursorMode, Fullscreen, Position, Size, Visibility};
+use super::{
+  cursor::CursorImage,
+  data::{
+    CursorMode,
+    Fullscreen,
+    Position,
+    Size,
+    TimerId,
+    UserAttention,
+    Visibility,
+    WindowLevel,
+  },
+  inhibit::InhibitFlags,
+  menu::{Menu, MenuId},
+  suppress::KeySuppression,
+  tray::{Balloon, TrayIcon},
+};
 
 #[repr(u32)]
 #[derive(Debug, Clone, PartialEq)]
@@ -21,9 +38,33 @@ pub enum Command {
   SetSize(Size),
   SetPosition(Position),
   SetFullscreen(Option<Fullscreen>),
-  SetCursorIcon(CursorIcon),
+  SetMinimized(bool),
+  SetMaximized(bool),
+  Restore,
+  SetTransparent(bool),
+  SetOpacity(f32),
+  SetCursorHittest(bool),
+  SetWindowLevel(WindowLevel),
+  SetSkipTaskbar(bool),
+  SetToolWindow(bool),
+  SetEnabled(bool),
+  Focus,
+  RequestUserAttention(Option<UserAttention>),
+  SetCursorIcon(CursorImage),
   SetCursorMode(CursorMode),
   SetCursorVisibility(Visibility),
+  SetCursorPosition(Position),
+  SetMenu(Option<Menu>),
+  SetMenuItemEnabled(MenuId, bool),
+  SetMenuItemChecked(MenuId, bool),
+  ShowContextMenu(Position, Menu),
+  SetTrayIcon(Option<TrayIcon>),
+  ShowBalloon(Balloon),
+  SetTimer(TimerId, Duration, bool),
+  KillTimer(TimerId),
+  Inhibit(usize, InhibitFlags),
+  Uninhibit(usize),
+  SetKeySuppression(KeySuppression),
 }
 
 impl Command {
@@ -1,6 +1,14 @@
+use std::{
+  hash::{Hash, Hasher},
+  sync::{Arc, OnceLock},
+  time::Duration,
+};
+
 use cursor_icon::CursorIcon;
+use windows::Win32::UI::WindowsAndMessaging::{DestroyCursor, HCURSOR};
 
-use crate::{CursorMode, PhysicalPosition, Visibility};
+use super::icon::Icon;
+use crate::{error::WindowError, CursorMode, PhysicalPosition, Visibility};
 
 #[derive(Debug, Clone)]
 pub struct Cursor {
@@ -8,5 +16,178 @@ pub struct Cursor {
   pub visibility: Visibility,
   pub inside_window: bool,
   pub last_position: PhysicalPosition,
-  pub selected_icon: CursorIcon,
+  pub selected_icon: CursorImage,
+  /// Frame of an animated [`CustomCursor`] currently shown.
+  pub frame: usize,
+}
+
+/// Cursor shown while the cursor is in the client area. Set with
+/// [`Window::set_cursor_icon`](super::Window::set_cursor_icon).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CursorImage {
+  /// System cursor. Icons without a matching system cursor are shown as the arrow.
+  Icon(CursorIcon),
+  Custom(CustomCursor),
+}
+
+impl Default for CursorImage {
+  fn default() -> Self {
+    Self::Icon(CursorIcon::Default)
+  }
+}
+
+impl From<CursorIcon> for CursorImage {
+  fn from(value: CursorIcon) -> Self {
+    Self::Icon(value)
+  }
+}
+
+impl From<CustomCursor> for CursorImage {
+  fn from(value: CustomCursor) -> Self {
+    Self::Custom(value)
+  }
+}
+
+/// Cursor image built from RGBA pixels. Cursors with more than one frame are animated.
+///
+/// Clones share the same cursor and compare equal, while separately created cursors never
+/// do, even with identical pixels. The system cursors are created the first time the cursor
+/// is shown, and destroyed along with the last clone.
+#[derive(Debug, Clone)]
+pub struct CustomCursor(Arc<CustomCursorInner>);
+
+#[derive(Debug)]
+struct CustomCursorInner {
+  frames: Vec<Icon>,
+  /// Pixel of the image that points at the cursor position.
+  hotspot: PhysicalPosition,
+  frame_duration: Duration,
+  handle: OnceLock<Option<CustomCursorHandle>>,
+}
+
+impl PartialEq for CustomCursor {
+  fn eq(&self, other: &Self) -> bool {
+    Arc::ptr_eq(&self.0, &other.0)
+  }
+}
+
+impl Eq for CustomCursor {}
+
+impl Hash for CustomCursor {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    Arc::as_ptr(&self.0).hash(state);
+  }
+}
+
+impl CustomCursor {
+  pub fn from_rgba(
+    rgba: Vec<u8>,
+    width: u32,
+    height: u32,
+    hotspot: PhysicalPosition,
+  ) -> Result<Self, WindowError> {
+    let frame = Icon::from_rgba(rgba, width, height)?;
+    Self::animated(vec![frame], hotspot, Duration::ZERO)
+  }
+
+  /// Frames must all be the same size, and are each shown for `frame_duration` before
+  /// looping.
+  pub fn animated(
+    frames: Vec<Icon>,
+    hotspot: PhysicalPosition,
+    frame_duration: Duration,
+  ) -> Result<Self, WindowError> {
+    let Some(first) = frames.first() else {
+      return Err(WindowError::Error("cursor must have at least one frame".to_owned()));
+    };
+    let (width, height) = (first.width(), first.height());
+    if frames
+      .iter()
+      .any(|frame| frame.width() != width || frame.height() != height)
+    {
+      return Err(WindowError::Error(format!(
+        "expected all cursor frames to be {width}x{height}"
+      )));
+    }
+    if hotspot.x < 0
+      || hotspot.y < 0
+      || hotspot.x as u32 >= width
+      || hotspot.y as u32 >= height
+    {
+      return Err(WindowError::Error(format!(
+        "cursor hotspot {hotspot:?} is outside of the {width}x{height} image"
+      )));
+    }
+    if frames.len() > 1 && frame_duration.is_zero() {
+      return Err(WindowError::Error(
+        "animated cursor frames must have a duration".to_owned(),
+      ));
+    }
+
+    Ok(Self(Arc::new(CustomCursorInner {
+      frames,
+      hotspot,
+      frame_duration,
+      handle: OnceLock::new(),
+    })))
+  }
+
+  pub fn frames(&self) -> &[Icon] {
+    &self.0.frames
+  }
+
+  pub fn hotspot(&self) -> PhysicalPosition {
+    self.0.hotspot
+  }
+
+  pub fn frame_duration(&self) -> Duration {
+    self.0.frame_duration
+  }
+
+  pub fn is_animated(&self) -> bool {
+    self.0.frames.len() > 1
+  }
+
+  /// System cursors of the frames, created on first use. Returns [`None`] if they could not
+  /// be created.
+  pub(crate) fn handle(&self) -> Option<&CustomCursorHandle> {
+    self
+      .0
+      .handle
+      .get_or_init(|| match CustomCursorHandle::new(self) {
+        Ok(handle) => Some(handle),
+        Err(e) => {
+          tracing::error!("{e}");
+          None
+        }
+      })
+      .as_ref()
+  }
+}
+
+/// Cursors created from the frames of a [`CustomCursor`], destroyed on drop.
+#[derive(Debug)]
+pub(crate) struct CustomCursorHandle(Vec<HCURSOR>);
+
+impl CustomCursorHandle {
+  pub(crate) fn new(cursor: &CustomCursor) -> Result<Self, WindowError> {
+    // frames created so far are destroyed on drop if a later one fails
+    let mut handle = Self(Vec::with_capacity(cursor.frames().len()));
+    for frame in cursor.frames() {
+      handle.0.push(frame.to_cursor(cursor.hotspot())?);
+    }
+    Ok(handle)
+  }
+
+  pub(crate) fn frame(&self, index: usize) -> HCURSOR {
+    self.0[index % self.0.len()]
+  }
+}
+
+impl Drop for CustomCursorHandle {
+  fn drop(&mut self) {
+    for hcursor in &self.0 {
+      let _ = unsafe { DestroyCursor(*hcursor) };
+    }
+  }
 }
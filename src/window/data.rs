@@ -1,4 +1,5 @@
 use std::{
+  collections::{HashMap, VecDeque},
   ops::{Div, Mul},
   sync::{Arc, Condvar, Mutex, MutexGuard},
   thread::JoinHandle,
@@ -7,36 +8,87 @@ use std::{
 use windows::{
   core::PCWSTR,
   Win32::{
-    Foundation::{HINSTANCE, HWND, LPARAM, LRESULT, POINT, RECT, WPARAM},
-    Graphics::Gdi::{
-      self,
-      ClientToScreen,
-      GetMonitorInfoW,
-      InvalidateRgn,
-      MonitorFromWindow,
-      RedrawWindow,
-      MONITORINFO,
+    Foundation::{
+      BOOL,
+      COLORREF,
+      HINSTANCE,
+      HWND,
+      LPARAM,
+      LRESULT,
+      POINT,
+      RECT,
+      SIZE,
+      WPARAM,
+    },
+    Graphics::{
+      Dwm::{self, DwmFlush},
+      Gdi::{
+        self,
+        BeginPaint,
+        ClientToScreen,
+        EndPaint,
+        GetMonitorInfoW,
+        InvalidateRgn,
+        MonitorFromWindow,
+        MONITORINFO,
+        PAINTSTRUCT,
+      },
+    },
+    System::{
+      Power::{HPOWERNOTIFY, POWERBROADCAST_SETTING},
+      SystemServices::{
+        MK_LBUTTON,
+        MK_MBUTTON,
+        MK_RBUTTON,
+        MK_XBUTTON1,
+        MK_XBUTTON2,
+        MODIFIERKEYS_FLAGS,
+      },
     },
     UI::{
       self,
+      Accessibility::HWINEVENTHOOK,
       Controls,
+      HiDpi::AdjustWindowRectExForDpi,
       Input::{
-        KeyboardAndMouse::{self, TrackMouseEvent, TRACKMOUSEEVENT},
+        KeyboardAndMouse::{
+          self,
+          EnableWindow,
+          ReleaseCapture,
+          SetCapture,
+          TrackMouseEvent,
+          TRACKMOUSEEVENT,
+        },
         HRAWINPUT,
         RID_DEVICE_INFO_TYPE,
       },
+      TextServices::HKL,
       WindowsAndMessaging::{
         self,
+        CheckMenuItem,
         DefWindowProcW,
+        DrawMenuBar,
+        EnableMenuItem,
+        FlashWindowEx,
         GetClientRect,
+        GetMenu,
         GetWindowRect,
+        IsIconic,
+        IsWindowVisible,
+        KillTimer,
         LoadCursorW,
+        PostMessageW,
         SetCursor,
+        SetCursorPos,
+        SetForegroundWindow,
+        SetLayeredWindowAttributes,
+        SetTimer,
         SetWindowLongW,
         SetWindowPos,
         SetWindowTextW,
         ShowWindow,
         UnregisterClassW,
+        FLASHWINFO,
         WINDOWPOS,
       },
     },
@@ -45,11 +97,20 @@ use windows::{
 
 use super::{
   command::Command,
-  cursor::Cursor,
+  cursor::{Cursor, CursorImage},
   frame::Style,
-  input::mouse::mouse_button_states,
-  message::{get_cursor_move_kind, CursorMoveKind, Focus},
+  hotkey::{Hotkey, HotkeyId},
+  inhibit::{set_execution_state, InhibitCondition, InhibitFlags},
+  input::{
+    layout::KeyboardLayout,
+    mouse::{mouse_button_states, Scroll, ScrollPhase},
+  },
+  menu::{set_window_menu, AcceleratorTable, Menu, MenuCheck, MenuId},
+  message::{get_cursor_move_kind, CursorMoveKind, DisplayState, Focus, SystemCommand},
+  present::Framebuffer,
   stage::Stage,
+  suppress::{self, KeySuppression},
+  tray::{taskbar_created_message, Tray},
 };
 use crate::{
   error::WindowError,
@@ -59,21 +120,46 @@ use crate::{
     get_window_ex_style,
     get_window_style,
     hi_word,
+    is_cloaked,
+    is_dark_mode_supported,
     is_flag_set,
+    is_high_contrast_enabled,
+    is_layered,
+    is_legacy_dark_mode_attribute,
+    is_system_dark_mode_enabled,
     lo_word,
     read_raw_input,
+    set_blur_behind,
+    set_dwm_attribute,
+    set_taskbar_tab,
     signed_hi_word,
     signed_lo_word,
+    system_accent_color,
     to_windows_cursor,
   },
   window::Input,
+  ButtonState,
   Key,
+  LoopMessage,
   Message,
   MouseButton,
   RawInputMessage,
   RawKeyState,
+  Window,
 };
 
+/// Timer that keeps frames coming while the window is in the modal size and move loop.
+/// Ids of user timers count up from one, so they never reach it.
+const SIZE_MOVE_TIMER_ID: usize = usize::MAX;
+/// Roughly one frame at 60 Hz.
+const SIZE_MOVE_TIMER_INTERVAL: u32 = 16;
+/// Timer that advances the frames of an animated
+/// [`CustomCursor`](super::cursor::CustomCursor).
+const CURSOR_ANIMATION_TIMER_ID: usize = usize::MAX - 1;
+/// Timer that ends a scroll gesture once no wheel messages have arrived for a while.
+const SCROLL_END_TIMER_ID: usize = usize::MAX - 2;
+const SCROLL_END_TIMEOUT: u32 = 100;
+
 #[derive(Clone)]
 pub struct SyncData {
   pub new_message: Arc<(Mutex<bool>, Condvar)>,
@@ -99,6 +185,22 @@ impl SyncData {
     *next = *self.skip_wait.lock().unwrap();
   }
 
+  /// Stops the window thread from waiting on the main thread between messages. Messages
+  /// sent in the meantime are queued until the main thread takes them.
+  pub fn release(&self) {
+    *self.skip_wait.lock().unwrap() = true;
+    self.signal_next_frame();
+  }
+
+  pub fn is_released(&self) -> bool {
+    *self.skip_wait.lock().unwrap()
+  }
+
+  /// Undoes [`SyncData::release`].
+  pub fn resume(&self) {
+    *self.skip_wait.lock().unwrap() = false;
+  }
+
   pub fn signal_next_frame(&self) {
     let (lock, cvar) = self.next_frame.as_ref();
     let mut next = lock.lock().unwrap();
@@ -113,10 +215,17 @@ pub struct Internal {
   pub hinstance: HINSTANCE,
   pub hwnd: HWND,
   pub class_atom: u16,
-  pub message: Arc<Mutex<Option<Message>>>,
+  /// Owner or parent of the window.
+  pub owner: Option<Window>,
+  /// Messages waiting to be taken by the main thread. Holds at most one message unless the
+  /// window thread has been released.
+  pub message: Arc<Mutex<VecDeque<Message>>>,
   pub sync: SyncData,
   pub thread: Mutex<Option<JoinHandle<Result<(), WindowError>>>>,
   pub data: Mutex<Data>,
+  /// Last frame presented with [`Window::present`]. Kept apart from the data so that
+  /// drawing it does not block the window thread.
+  pub(crate) framebuffer: Mutex<Option<Framebuffer>>,
 }
 
 /// Window is destroyed on drop.
@@ -132,9 +241,23 @@ impl Drop for Internal {
 
     tracing::trace!("[`{}`]: destroying window", title);
 
+    self.end_modal();
+
+    // Destroying a window synchronously notifies its owner, whose thread would otherwise be
+    // stuck waiting on the main thread, which is in turn waiting on this window.
+    if let Some(owner) = &self.owner {
+      owner.0.sync.release();
+    }
+
     Command::Destroy.post(self.hwnd);
     self.join_thread();
 
+    if let Some(owner) = &self.owner {
+      if !owner.0.is_closing() {
+        owner.0.sync.resume();
+      }
+    }
+
     tracing::trace!("[`{}`]: unregistering window class", title);
     unsafe { UnregisterClassW(PCWSTR(self.class_atom as *const u16), self.hinstance) }
       .unwrap();
@@ -146,7 +269,19 @@ impl Drop for Internal {
 pub struct Data {
   pub title: String,
   pub subtitle: String,
+  /// Resolved theme currently applied to the frame.
   pub theme: Theme,
+  /// Theme requested by the user, which may be [`Theme::Auto`].
+  pub requested_theme: Theme,
+  /// System accent color last sent to the main thread.
+  pub accent_color: Option<Color>,
+  /// High contrast state last sent to the main thread.
+  pub high_contrast: bool,
+  pub backdrop: Backdrop,
+  pub corner_preference: CornerPreference,
+  pub border_color: Option<Color>,
+  pub caption_color: Option<Color>,
+  pub caption_text_color: Option<Color>,
   pub flow: Flow,
   pub close_on_x: bool,
 
@@ -154,12 +289,51 @@ pub struct Data {
   pub style: Style,
   pub input: Input,
   pub cursor: Cursor,
+  /// Whether the mouse is captured while a button is held.
+  pub mouse_capture: bool,
+  /// Whether the window currently holds a capture it set itself.
+  pub mouse_captured: bool,
+  pub(crate) scroll: Scroll,
+  pub key_suppression: KeySuppression,
+  /// Layout of the window thread.
+  pub keyboard_layout: KeyboardLayout,
+  pub modal: bool,
+  pub menu: Option<Menu>,
+  /// Accelerators of the enabled items of the menu bar.
+  pub(crate) accelerators: Option<AcceleratorTable>,
+  pub(crate) tray: Option<Tray>,
+  pub next_timer_id: usize,
+  /// Running timers, and whether they repeat.
+  pub timers: HashMap<TimerId, bool>,
+  /// Hotkeys that have not been unregistered.
+  pub hotkeys: HashMap<HotkeyId, Hotkey>,
+  pub next_inhibitor_id: usize,
+  /// Flags of the inhibitors that have not been dropped.
+  pub inhibitors: HashMap<usize, InhibitFlags>,
+  /// Occlusion state last sent to the main thread.
+  pub occluded: bool,
+  pub occlusion_hook: Option<HWINEVENTHOOK>,
+  /// Whether power, session, and display state messages are sent.
+  pub power_events: bool,
+  /// Display state last sent to the main thread.
+  pub display_state: Option<DisplayState>,
+  pub display_notification: Option<HPOWERNOTIFY>,
+  /// Minimized, maximized, and fullscreen state last sent to the main thread.
+  pub reported_state: (bool, bool, bool),
+  /// Show command for the first time the window is shown, taken from the settings.
+  pub initial_show: Option<WindowsAndMessaging::SHOW_WINDOW_CMD>,
 
   pub last_windowed_position: Position,
   pub last_windowed_size: Size,
   pub scale_factor: f64,
+  /// Inner size to apply once [`Message::ScaleFactorChanged`] has been handled. Only set
+  /// while it is being handled, and replaced through
+  /// [`Window::set_scale_factor_inner_size`](super::Window::set_scale_factor_inner_size).
+  pub scale_factor_inner_size: Option<PhysicalSize>,
 
+  /// Whether a [`Message::RedrawRequested`] is pending.
   pub requested_redraw: bool,
+  pub redraw_pacing: RedrawPacing,
 }
 
 impl Internal {
@@ -172,12 +346,18 @@ impl Internal {
   }
 
   pub fn send_message_to_main(&self, message: Message) {
-    let should_wait = self.message.lock().unwrap().is_some();
-    if should_wait {
+    if self.sync.is_released() {
+      self.message.lock().unwrap().push_back(message);
+      self.sync.signal_new_message();
+      return;
+    }
+
+    // Messages queued while released are drained before returning to lockstep
+    while !self.message.lock().unwrap().is_empty() && !self.sync.is_released() {
       self.sync.wait_on_frame();
     }
 
-    self.message.lock().unwrap().replace(message);
+    self.message.lock().unwrap().push_back(message);
     self.sync.signal_new_message();
 
     // TODO: try inverting these locks so that they don't lock unless the main thread tells them to lock.
@@ -201,8 +381,37 @@ impl Internal {
     )
   }
 
+  /// Re-enables the owner of a modal window.
+  pub(crate) fn end_modal(&self) {
+    let was_modal = std::mem::take(&mut self.data_lock().modal);
+    if let (true, Some(owner)) = (was_modal, &self.owner) {
+      Command::SetEnabled(true).post(owner.0.hwnd);
+      Command::Focus.post(owner.0.hwnd);
+    }
+  }
+
   // pub(crate) fn exit_loop(&self) {
   // }
+  /// Shows the selected system cursor, or the current frame of the selected custom
+  /// cursor.
+  pub(crate) fn show_cursor_icon(&self) {
+    let hcursor = {
+      let data = self.data_lock();
+      match &data.cursor.selected_icon {
+        CursorImage::Icon(icon) => {
+          unsafe { LoadCursorW(HINSTANCE::default(), to_windows_cursor(*icon)) }.ok()
+        }
+        CursorImage::Custom(custom) => custom
+          .handle()
+          .map(|handle| handle.frame(data.cursor.frame)),
+      }
+    };
+
+    if let Some(hcursor) = hcursor {
+      unsafe { SetCursor(hcursor) };
+    }
+  }
+
   pub fn refresh_os_cursor(&self) -> Result<(), WindowError> {
     let mut client_rect = RECT::default();
     unsafe { GetClientRect(self.hwnd, &mut client_rect) }.unwrap();
@@ -217,27 +426,32 @@ impl Internal {
       let style = &self.data_lock().style;
       style.focused && style.active
     };
+    let cursor_mode = self.data_lock().cursor.mode;
     if is_focused {
-      let is_confined = matches!(self.data_lock().cursor.mode, CursorMode::Confined);
       let is_hidden = matches!(self.data_lock().cursor.visibility, Visibility::Hidden);
-      let cursor_clip = match is_confined {
-        true => {
-          if is_hidden {
-            // Confine the cursor to the center of the window if the cursor is hidden. This avoids
-            // problems with the cursor activating the taskbar if the window borders or overlaps that.
-            let cx = (client_rect.left + client_rect.right) / 2;
-            let cy = (client_rect.top + client_rect.bottom) / 2;
-            Some(RECT {
-              left: cx,
-              right: cx + 1,
-              top: cy,
-              bottom: cy + 1,
-            })
-          } else {
-            Some(client_rect)
-          }
+      let center = {
+        let cx = (client_rect.left + client_rect.right) / 2;
+        let cy = (client_rect.top + client_rect.bottom) / 2;
+        RECT {
+          left: cx,
+          right: cx + 1,
+          top: cy,
+          bottom: cy + 1,
         }
-        false => None,
+      };
+      let cursor_clip = match cursor_mode {
+        CursorMode::Normal => None,
+        // Confine the cursor to the center of the window if the cursor is hidden. This avoids
+        // problems with the cursor activating the taskbar if the window borders or overlaps that.
+        CursorMode::Confined if is_hidden => Some(center),
+        CursorMode::Confined => Some(client_rect),
+        CursorMode::ConfinedTo(rect) => Some(RECT {
+          left: client_rect.left + rect.position.x,
+          top: client_rect.top + rect.position.y,
+          right: client_rect.left + rect.position.x + rect.size.width as i32,
+          bottom: client_rect.top + rect.position.y + rect.size.height as i32,
+        }),
+        CursorMode::Locked => Some(center),
       };
 
       let rect_to_tuple = |rect: RECT| (rect.left, rect.top, rect.right, rect.bottom);
@@ -257,7 +471,10 @@ impl Internal {
       }
     }
 
-    let cursor_visibility = self.data_lock().cursor.visibility;
+    let cursor_visibility = match cursor_mode {
+      CursorMode::Locked => Visibility::Hidden,
+      _ => self.data_lock().cursor.visibility,
+    };
     let cursor_in_client = self.data_lock().cursor.inside_window;
     if cursor_in_client {
       utilities::set_cursor_visibility(cursor_visibility);
@@ -268,6 +485,260 @@ impl Internal {
     Ok(())
   }
 
+  /// Captures the mouse while any button is held, and releases it once none are. `held` are
+  /// the buttons reported as held by the mouse message.
+  fn refresh_mouse_capture(&self, hwnd: HWND, held: MODIFIERKEYS_FLAGS) {
+    let any_held = (held
+      & (MK_LBUTTON | MK_MBUTTON | MK_RBUTTON | MK_XBUTTON1 | MK_XBUTTON2))
+      != MODIFIERKEYS_FLAGS(0);
+
+    let mut data = self.data_lock();
+    if any_held && data.mouse_capture && !data.mouse_captured {
+      data.mouse_captured = true;
+      drop(data);
+      unsafe { SetCapture(hwnd) };
+    } else if !any_held && data.mouse_captured {
+      // cleared first so that `WM_CAPTURECHANGED` does not release the buttons again
+      data.mouse_captured = false;
+      drop(data);
+      if let Err(e) = unsafe { ReleaseCapture() } {
+        tracing::error!("{e}");
+      }
+    }
+  }
+
+  /// Reapplies the extended window style, along with the alpha of layered windows.
+  pub(crate) fn refresh_ex_style(&self, hwnd: HWND) {
+    let style = self.data_lock().style.clone();
+    unsafe {
+      SetWindowLongW(
+        hwnd,
+        WindowsAndMessaging::GWL_EXSTYLE,
+        get_window_ex_style(&style).0 as i32,
+      )
+    };
+
+    if is_layered(&style) {
+      let alpha = (style.opacity.clamp(0.0, 1.0) * 255.0).round() as u8;
+      if let Err(e) = unsafe {
+        SetLayeredWindowAttributes(
+          hwnd,
+          COLORREF(0),
+          alpha,
+          WindowsAndMessaging::LWA_ALPHA,
+        )
+      } {
+        tracing::error!("{e}");
+      }
+    }
+
+    unsafe {
+      SetWindowPos(
+        hwnd,
+        None,
+        0,
+        0,
+        0,
+        0,
+        WindowsAndMessaging::SWP_NOZORDER
+          | WindowsAndMessaging::SWP_NOMOVE
+          | WindowsAndMessaging::SWP_NOSIZE
+          | WindowsAndMessaging::SWP_NOACTIVATE
+          | WindowsAndMessaging::SWP_FRAMECHANGED,
+      )
+      .expect("Failed to refresh window style");
+    }
+  }
+
+  /// Sends [`Message::StateChanged`] if the window state differs from the last one sent.
+  pub(crate) fn report_state(&self) {
+    let (state, changed) = {
+      let mut data = self.data_lock();
+      let state =
+        (data.style.minimized, data.style.maximized, data.style.fullscreen.is_some());
+      let changed = data.reported_state != state;
+      data.reported_state = state;
+      (state, changed)
+    };
+
+    if changed {
+      let (minimized, maximized, fullscreen) = state;
+      self.send_message_to_main(Message::StateChanged {
+        minimized,
+        maximized,
+        fullscreen,
+      });
+    }
+  }
+
+  /// Resolves the theme against the system settings and applies it to the frame. Returns
+  /// the resolved theme.
+  pub(crate) fn apply_theme(&self, theme: Theme) -> Theme {
+    let resolved = match theme {
+      Theme::Auto => {
+        if is_system_dark_mode_enabled() {
+          Theme::Dark
+        } else {
+          Theme::Light
+        }
+      }
+      Theme::Dark => {
+        if is_dark_mode_supported() {
+          Theme::Dark
+        } else {
+          Theme::Light
+        }
+      }
+      Theme::Light => Theme::Light,
+    };
+
+    {
+      let mut data = self.data_lock();
+      data.requested_theme = theme;
+      data.theme = resolved;
+    }
+
+    let dark_mode = BOOL::from(resolved == Theme::Dark);
+    let attribute = if is_legacy_dark_mode_attribute() {
+      Dwm::DWMWINDOWATTRIBUTE(19)
+    } else {
+      Dwm::DWMWA_USE_IMMERSIVE_DARK_MODE
+    };
+    set_dwm_attribute(self.hwnd, attribute, &dark_mode);
+
+    resolved
+  }
+
+  /// Re-applies [`Theme::Auto`] and sends the system colors that changed since they were
+  /// last read.
+  pub(crate) fn refresh_system_colors(&self) {
+    let (requested_theme, previous_theme) = {
+      let data = self.data_lock();
+      (data.requested_theme, data.theme)
+    };
+    if requested_theme == Theme::Auto {
+      let theme = self.apply_theme(Theme::Auto);
+      if theme != previous_theme {
+        self.send_message_to_main(Message::ThemeChanged(theme));
+      }
+    }
+
+    let accent_color = system_accent_color();
+    let accent_changed = {
+      let mut data = self.data_lock();
+      let changed = data.accent_color != accent_color;
+      data.accent_color = accent_color;
+      changed
+    };
+    if let (true, Some(accent_color)) = (accent_changed, accent_color) {
+      self.send_message_to_main(Message::AccentColorChanged(accent_color));
+    }
+
+    let high_contrast = is_high_contrast_enabled();
+    let high_contrast_changed = {
+      let mut data = self.data_lock();
+      let changed = data.high_contrast != high_contrast;
+      data.high_contrast = high_contrast;
+      changed
+    };
+    if high_contrast_changed {
+      self.send_message_to_main(Message::HighContrastChanged(high_contrast));
+    }
+  }
+
+  /// Keeps the display or system awake as requested by the inhibitors that currently
+  /// apply.
+  pub(crate) fn refresh_inhibitors(&self) {
+    let data = self.data_lock();
+    set_execution_state(
+      data
+        .inhibitors
+        .values()
+        .filter(|flags| match flags.condition {
+          InhibitCondition::Always => true,
+          InhibitCondition::Focused => data.style.focused,
+          InhibitCondition::Fullscreen => data.style.fullscreen.is_some(),
+        }),
+    );
+  }
+
+  /// Applies the key suppression while the window is focused and fullscreen, and releases
+  /// it otherwise.
+  pub(crate) fn refresh_key_suppression(&self) {
+    let suppression = {
+      let data = self.data_lock();
+      (data.style.focused && data.style.fullscreen.is_some())
+        .then_some(data.key_suppression)
+    };
+    suppress::apply(self.hwnd, suppression);
+  }
+
+  /// Rebuilds the accelerator table from the menu bar. Must be called on the window thread.
+  pub(crate) fn refresh_accelerators(&self) {
+    let mut data = self.data_lock();
+    // the previous table is destroyed before the new one is created
+    data.accelerators = None;
+    data.accelerators = match data.menu.as_ref().map(Menu::accelerator_table) {
+      Some(Ok(table)) => table,
+      Some(Err(e)) => {
+        tracing::error!("{e}");
+        None
+      }
+      None => None,
+    };
+    if let Some(table) = &data.accelerators {
+      table.activate(self.hwnd);
+    }
+  }
+
+  /// Returns the outer size of the window for an inner size at the given DPI.
+  pub(crate) fn outer_size_for_dpi(
+    &self,
+    inner_size: PhysicalSize,
+    dpi: u32,
+  ) -> PhysicalSize {
+    let (style, has_menu) = {
+      let data = self.data_lock();
+      (data.style.clone(), data.menu.is_some())
+    };
+    let mut rect = RECT {
+      left: 0,
+      top: 0,
+      right: inner_size.width as i32,
+      bottom: inner_size.height as i32,
+    };
+    if let Err(e) = unsafe {
+      AdjustWindowRectExForDpi(
+        &mut rect,
+        get_window_style(&style),
+        has_menu,
+        get_window_ex_style(&style),
+        dpi,
+      )
+    } {
+      tracing::error!("{e}");
+    }
+    PhysicalSize::new((rect.right - rect.left) as u32, (rect.bottom - rect.top) as u32)
+  }
+
+  /// Sends [`Message::Occluded`] if the window was hidden, minimized, or cloaked, or
+  /// stopped being so.
+  pub(crate) fn refresh_occlusion(&self, hwnd: HWND) {
+    let occluded =
+      unsafe { IsIconic(hwnd).as_bool() || !IsWindowVisible(hwnd).as_bool() }
+        || is_cloaked(hwnd);
+    let changed = {
+      let mut data = self.data_lock();
+      let changed = data.occluded != occluded;
+      data.occluded = occluded;
+      changed
+    };
+
+    if changed {
+      self.send_message_to_main(Message::Occluded(occluded));
+    }
+  }
+
   pub(crate) fn update_last_windowed_pos_size(&self, hwnd: HWND) {
     let mut window_rect = RECT::default();
     let _ = unsafe { GetWindowRect(hwnd, &mut window_rect) };
@@ -297,15 +768,37 @@ impl Internal {
         match *command {
           Command::Exit => (),
           Command::Destroy => (),
-          Command::Redraw => unsafe {
-            RedrawWindow(hwnd, None, None, Gdi::RDW_INTERNALPAINT);
-          },
-          Command::SetVisibility(visibility) => unsafe {
-            ShowWindow(hwnd, match visibility {
-              Visibility::Hidden => WindowsAndMessaging::SW_HIDE,
-              Visibility::Shown => WindowsAndMessaging::SW_SHOW,
-            });
-          },
+          Command::Redraw => {
+            if self.data_lock().redraw_pacing == RedrawPacing::Compositor {
+              // blocks until the next composition, so at most one frame is drawn per refresh
+              let _ = unsafe { DwmFlush() };
+            }
+            // cleared first so that requests made while redrawing schedule the next frame
+            self.data_lock().requested_redraw = false;
+            self.send_message_to_main(Message::RedrawRequested);
+          }
+          Command::SetVisibility(visibility) => {
+            let command = {
+              let mut data = self.data.lock().unwrap();
+              let initial_show = match visibility {
+                Visibility::Shown => data.initial_show.take(),
+                Visibility::Hidden => None,
+              };
+              show_command(
+                visibility,
+                initial_show,
+                data.style.minimized,
+                data.style.maximized,
+              )
+            };
+            unsafe { ShowWindow(hwnd, command) };
+            // Explorer adds the taskbar button back whenever the window is shown
+            let skip_taskbar = self.data.lock().unwrap().style.skip_taskbar;
+            if visibility == Visibility::Shown && skip_taskbar {
+              set_taskbar_tab(hwnd, false);
+            }
+            self.refresh_occlusion(hwnd);
+          }
           Command::SetDecorations(decorations) => {
             let style = self.data.lock().unwrap().style.clone();
             match decorations {
@@ -476,13 +969,260 @@ impl Internal {
                 unsafe { InvalidateRgn(hwnd, None, false) };
               }
             }
+            self.report_state();
+            self.refresh_inhibitors();
+            self.refresh_key_suppression();
+          }
+          Command::SetMenu(menu) => {
+            if let Err(e) = set_window_menu(hwnd, menu.as_ref()) {
+              tracing::error!("{e}");
+            }
+            self.data.lock().unwrap().menu = menu;
+            self.refresh_accelerators();
+          }
+          Command::SetMenuItemEnabled(id, enabled) => {
+            if let Some(item) =
+              self.data_lock().menu.as_mut().and_then(|m| m.item_mut(id))
+            {
+              item.enabled = enabled;
+            }
+            self.refresh_accelerators();
+            unsafe {
+              let hmenu = GetMenu(hwnd);
+              EnableMenuItem(
+                hmenu,
+                id.0 as u32,
+                WindowsAndMessaging::MF_BYCOMMAND
+                  | match enabled {
+                    true => WindowsAndMessaging::MF_ENABLED,
+                    false => WindowsAndMessaging::MF_GRAYED,
+                  },
+              );
+              let _ = DrawMenuBar(hwnd);
+            }
+          }
+          Command::SetMenuItemChecked(id, checked) => {
+            if let Some(item) =
+              self.data_lock().menu.as_mut().and_then(|m| m.item_mut(id))
+            {
+              item.check = match item.check {
+                Some(MenuCheck::Radio(_)) => Some(MenuCheck::Radio(checked)),
+                _ => Some(MenuCheck::Check(checked)),
+              };
+            }
+            unsafe {
+              let hmenu = GetMenu(hwnd);
+              CheckMenuItem(
+                hmenu,
+                id.0 as u32,
+                (WindowsAndMessaging::MF_BYCOMMAND
+                  | match checked {
+                    true => WindowsAndMessaging::MF_CHECKED,
+                    false => WindowsAndMessaging::MF_UNCHECKED,
+                  })
+                .0,
+              );
+            }
+          }
+          Command::ShowContextMenu(position, menu) => {
+            let mut point = {
+              let position = position.as_physical(self.data_lock().scale_factor);
+              POINT {
+                x: position.x,
+                y: position.y,
+              }
+            };
+            unsafe { ClientToScreen(hwnd, &mut point) };
+            if let Err(e) = menu.show_popup(hwnd, point.x, point.y) {
+              tracing::error!("{e}");
+            }
+          }
+          Command::SetCursorPosition(position) => {
+            let mut point = {
+              let position = position.as_physical(self.data_lock().scale_factor);
+              POINT {
+                x: position.x,
+                y: position.y,
+              }
+            };
+            unsafe { ClientToScreen(hwnd, &mut point) };
+            if let Err(e) = unsafe { SetCursorPos(point.x, point.y) } {
+              tracing::error!("{e}");
+            }
+          }
+          Command::SetTrayIcon(tray_icon) => {
+            let previous = self.data_lock().tray.take();
+            match tray_icon {
+              Some(tray_icon) => match Tray::show(hwnd, tray_icon, previous.as_ref()) {
+                // the previous icon handle is only destroyed once replaced
+                Ok(tray) => self.data_lock().tray = Some(tray),
+                Err(e) => {
+                  tracing::error!("{e}");
+                  self.data_lock().tray = previous;
+                }
+              },
+              None if previous.is_some() => Tray::remove(hwnd),
+              None => (),
+            }
+          }
+          Command::ShowBalloon(balloon) => {
+            if let Some(tray) = &self.data_lock().tray {
+              if let Err(e) = tray.show_balloon(hwnd, &balloon) {
+                tracing::error!("{e}");
+              }
+            }
+          }
+          Command::SetTimer(id, duration, repeat) => {
+            // rounded up so that short timers still wait
+            let millis = duration
+              .as_nanos()
+              .div_ceil(1_000_000)
+              .min(u32::MAX as u128) as u32;
+            if unsafe { SetTimer(hwnd, id.0, millis, None) } == 0 {
+              tracing::error!("{}", windows::core::Error::from_win32());
+            } else {
+              self.data_lock().timers.insert(id, repeat);
+            }
+          }
+          Command::KillTimer(id) => {
+            if self.data_lock().timers.remove(&id).is_some() {
+              let _ = unsafe { KillTimer(hwnd, id.0) };
+            }
+          }
+          Command::Inhibit(id, flags) => {
+            self.data_lock().inhibitors.insert(id, flags);
+            self.refresh_inhibitors();
+          }
+          Command::Uninhibit(id) => {
+            self.data_lock().inhibitors.remove(&id);
+            self.refresh_inhibitors();
+          }
+          Command::SetKeySuppression(key_suppression) => {
+            self.data_lock().key_suppression = key_suppression;
+            self.refresh_key_suppression();
+          }
+          Command::SetMinimized(minimized) => unsafe {
+            ShowWindow(hwnd, match minimized {
+              true => WindowsAndMessaging::SW_MINIMIZE,
+              false => WindowsAndMessaging::SW_RESTORE,
+            });
+          },
+          Command::SetMaximized(maximized) => unsafe {
+            ShowWindow(hwnd, match maximized {
+              true => WindowsAndMessaging::SW_MAXIMIZE,
+              false => WindowsAndMessaging::SW_RESTORE,
+            });
+          },
+          Command::Restore => unsafe {
+            ShowWindow(hwnd, WindowsAndMessaging::SW_RESTORE);
+          },
+          Command::SetTransparent(transparent) => {
+            self.data.lock().unwrap().style.transparent = transparent;
+            set_blur_behind(hwnd, transparent);
+          }
+          Command::SetOpacity(opacity) => {
+            self.data.lock().unwrap().style.opacity = opacity;
+            self.refresh_ex_style(hwnd);
+          }
+          Command::SetCursorHittest(hittest) => {
+            self.data.lock().unwrap().style.cursor_hittest = hittest;
+            self.refresh_ex_style(hwnd);
+          }
+          Command::SetWindowLevel(level) => {
+            self.data.lock().unwrap().style.level = level;
+            let insert_after = match level {
+              WindowLevel::Normal => WindowsAndMessaging::HWND_NOTOPMOST,
+              WindowLevel::AlwaysOnTop => WindowsAndMessaging::HWND_TOPMOST,
+              WindowLevel::AlwaysOnBottom => WindowsAndMessaging::HWND_BOTTOM,
+            };
+            unsafe {
+              SetWindowPos(
+                hwnd,
+                insert_after,
+                0,
+                0,
+                0,
+                0,
+                WindowsAndMessaging::SWP_NOMOVE
+                  | WindowsAndMessaging::SWP_NOSIZE
+                  | WindowsAndMessaging::SWP_NOACTIVATE,
+              )
+              .expect("Failed to set window level");
+            }
+          }
+          Command::SetSkipTaskbar(skip_taskbar) => {
+            self.data.lock().unwrap().style.skip_taskbar = skip_taskbar;
+            self.refresh_ex_style(hwnd);
+            set_taskbar_tab(hwnd, !skip_taskbar);
+          }
+          Command::SetToolWindow(tool_window) => {
+            self.data.lock().unwrap().style.tool_window = tool_window;
+            self.refresh_ex_style(hwnd);
+          }
+          Command::SetEnabled(enabled) => unsafe {
+            EnableWindow(hwnd, enabled);
+          },
+          Command::Focus => {
+            if unsafe { IsIconic(hwnd) }.as_bool() {
+              unsafe { ShowWindow(hwnd, WindowsAndMessaging::SW_RESTORE) };
+            }
+            unsafe { SetForegroundWindow(hwnd) };
+          }
+          Command::RequestUserAttention(attention) => {
+            let (flags, count) = match attention {
+              Some(UserAttention::Critical) => (
+                WindowsAndMessaging::FLASHW_ALL | WindowsAndMessaging::FLASHW_TIMERNOFG,
+                u32::MAX,
+              ),
+              Some(UserAttention::Informational) => (
+                WindowsAndMessaging::FLASHW_TRAY | WindowsAndMessaging::FLASHW_TIMERNOFG,
+                0,
+              ),
+              None => (WindowsAndMessaging::FLASHW_STOP, 0),
+            };
+            unsafe {
+              FlashWindowEx(&FLASHWINFO {
+                cbSize: std::mem::size_of::<FLASHWINFO>() as u32,
+                hwnd,
+                dwFlags: flags,
+                uCount: count,
+                dwTimeout: 0,
+              })
+            };
           }
           Command::SetCursorIcon(icon) => {
-            self.data.lock().unwrap().cursor.selected_icon = icon;
-            let cursor_icon = to_windows_cursor(icon);
-            let hcursor =
-              unsafe { LoadCursorW(HINSTANCE::default(), cursor_icon) }.unwrap();
-            unsafe { SetCursor(hcursor) };
+            let frame_duration = match &icon {
+              CursorImage::Icon(_) => None,
+              CursorImage::Custom(custom) => {
+                custom.is_animated().then_some(custom.frame_duration())
+              }
+            };
+
+            // kept until the new cursor is shown, as it may be the last clone of the shown
+            // custom cursor
+            let _previous = {
+              let mut data = self.data_lock();
+              data.cursor.frame = 0;
+              std::mem::replace(&mut data.cursor.selected_icon, icon)
+            };
+
+            match frame_duration {
+              Some(duration) => {
+                let millis = duration
+                  .as_nanos()
+                  .div_ceil(1_000_000)
+                  .min(u32::MAX as u128) as u32;
+                if unsafe { SetTimer(hwnd, CURSOR_ANIMATION_TIMER_ID, millis, None) } == 0
+                {
+                  tracing::error!("{}", windows::core::Error::from_win32());
+                }
+              }
+              None => {
+                let _ = unsafe { KillTimer(hwnd, CURSOR_ANIMATION_TIMER_ID) };
+              }
+            }
+
+            self.show_cursor_icon();
           }
           Command::SetCursorMode(mode) => {
             // match mode {
@@ -517,11 +1257,9 @@ impl Internal {
           lo_word(lparam.0 as u32) as u32 == WindowsAndMessaging::HTCLIENT;
 
         if in_client_area {
-          let icon = self.data.lock().unwrap().cursor.selected_icon;
-          let cursor_icon = to_windows_cursor(icon);
-          let hcursor =
-            unsafe { LoadCursorW(HINSTANCE::default(), cursor_icon) }.unwrap();
-          unsafe { SetCursor(hcursor) };
+          self.show_cursor_icon();
+          // the default handling would replace it with the class cursor
+          return LRESULT(1);
         }
 
         unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
@@ -535,21 +1273,57 @@ impl Internal {
         LRESULT(0)
       }
       WindowsAndMessaging::WM_PAINT => {
-        self.data.lock().unwrap().requested_redraw = false;
+        // the last presented frame is shown until a new one is presented
+        let painted = {
+          let framebuffer = self.framebuffer.lock().unwrap();
+          framebuffer.as_ref().map(|framebuffer| {
+            let mut client_rect = RECT::default();
+            let _ = unsafe { GetClientRect(hwnd, &mut client_rect) };
+            let client = PhysicalSize::new(
+              (client_rect.right - client_rect.left) as u32,
+              (client_rect.bottom - client_rect.top) as u32,
+            );
+
+            let mut paint = PAINTSTRUCT::default();
+            let hdc = unsafe { BeginPaint(hwnd, &mut paint) };
+            framebuffer.draw(hdc, client);
+            let _ = unsafe { EndPaint(hwnd, &paint) };
+          })
+        };
+
         self.send_message_to_main(Message::Paint);
-        unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+        match painted {
+          Some(()) => LRESULT(0),
+          None => unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) },
+        }
       }
       WindowsAndMessaging::WM_SIZE => {
-        self.data.lock().unwrap().style.maximized =
-          is_flag_set(wparam.0 as u32, WindowsAndMessaging::SIZE_MAXIMIZED);
+        let (minimized, maximized) = match wparam.0 as u32 {
+          WindowsAndMessaging::SIZE_MINIMIZED => (true, false),
+          WindowsAndMessaging::SIZE_MAXIMIZED => (false, true),
+          WindowsAndMessaging::SIZE_RESTORED => (false, false),
+          // sent to other windows when a window is maximized or restored
+          _ => {
+            let style = &self.data.lock().unwrap().style;
+            (style.minimized, style.maximized)
+          }
+        };
+        {
+          let style = &mut self.data.lock().unwrap().style;
+          style.minimized = minimized;
+          style.maximized = maximized;
+        }
 
         // info!("RESIZED: {_size:?}");
         let is_windowed = self.data.lock().unwrap().style.fullscreen.is_none();
         // // data.state.write_lock().size = size;
-        if is_windowed {
+        if is_windowed && !minimized && !maximized {
           self.update_last_windowed_pos_size(hwnd);
         }
 
+        self.report_state();
+        self.refresh_occlusion(hwnd);
+
         let width = lo_word(lparam.0 as u32) as u32;
         let height = hi_word(lparam.0 as u32) as u32;
 
@@ -563,6 +1337,19 @@ impl Internal {
         self.send_message_to_main(Message::Moved(PhysicalPosition::new(x, y)));
         unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
       }
+      WindowsAndMessaging::WM_WINDOWPOSCHANGING => {
+        let window_pos = unsafe { &mut *(lparam.0 as *mut WINDOWPOS) };
+
+        // keep the window below everything else whenever its z-order changes
+        let level = self.data.lock().unwrap().style.level;
+        if level == WindowLevel::AlwaysOnBottom
+          && !is_flag_set(window_pos.flags, WindowsAndMessaging::SWP_NOZORDER)
+        {
+          window_pos.hwndInsertAfter = WindowsAndMessaging::HWND_BOTTOM;
+        }
+
+        unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+      }
       WindowsAndMessaging::WM_WINDOWPOSCHANGED => {
         let window_pos = unsafe { &*(lparam.0 as *const WINDOWPOS) };
 
@@ -595,6 +1382,8 @@ impl Internal {
         if let Err(e) = self.refresh_os_cursor() {
           tracing::error!("{e}");
         };
+        self.refresh_inhibitors();
+        self.refresh_key_suppression();
         self.send_message_to_main(Message::Focus(Focus::Gained));
 
         unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
@@ -604,46 +1393,184 @@ impl Internal {
         if let Err(e) = self.refresh_os_cursor() {
           tracing::error!("{e}");
         };
+        self.refresh_inhibitors();
+        self.refresh_key_suppression();
         self.send_message_to_main(Message::Focus(Focus::Lost));
         unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
       }
-      WindowsAndMessaging::WM_COMMAND => {
-        self.send_message_to_main(Message::Command);
+      WindowsAndMessaging::WM_SETTINGCHANGE => {
+        let area = match lparam.0 {
+          0 => String::new(),
+          ptr => unsafe { PCWSTR(ptr as *const u16).to_string() }.unwrap_or_default(),
+        };
+        if area == "ImmersiveColorSet"
+          || wparam.0 as u32 == WindowsAndMessaging::SPI_SETHIGHCONTRAST.0
+        {
+          self.refresh_system_colors();
+        }
+        if [
+          WindowsAndMessaging::SPI_SETWHEELSCROLLLINES.0,
+          WindowsAndMessaging::SPI_SETWHEELSCROLLCHARS.0,
+        ]
+        .contains(&(wparam.0 as u32))
+        {
+          self.data_lock().scroll.refresh_settings();
+        }
         unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
       }
-      WindowsAndMessaging::WM_SYSCOMMAND => {
+      WindowsAndMessaging::WM_INPUTLANGCHANGE => {
+        let layout = KeyboardLayout::from_hkl(HKL(lparam.0));
+        let changed = {
+          let mut data = self.data_lock();
+          let changed = data.keyboard_layout != layout;
+          data.keyboard_layout = layout.clone();
+          changed
+        };
+        if changed {
+          self.send_message_to_main(Message::KeyboardLayoutChanged {
+            layout_id: layout.layout_id,
+            locale: layout.locale,
+          });
+        }
+        unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+      }
+      WindowsAndMessaging::WM_HOTKEY => {
+        // negative ids are reserved for the system
+        let id = u16::try_from(wparam.0 as i32).ok().map(HotkeyId);
+        match id.filter(|id| self.data_lock().hotkeys.contains_key(id)) {
+          Some(id) => {
+            self.send_message_to_main(Message::Hotkey(id));
+            LRESULT(0)
+          }
+          None => unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) },
+        }
+      }
+      WindowsAndMessaging::WM_POWERBROADCAST => {
+        if !self.data_lock().power_events {
+          return LRESULT(1);
+        }
+
+        match wparam.0 as u32 {
+          WindowsAndMessaging::PBT_APMSUSPEND => {
+            self.send_message_to_main(Message::Suspended);
+          }
+          // sent on every resume, unlike `PBT_APMRESUMESUSPEND` which requires user input
+          WindowsAndMessaging::PBT_APMRESUMEAUTOMATIC => {
+            self.send_message_to_main(Message::Resumed);
+          }
+          WindowsAndMessaging::PBT_POWERSETTINGCHANGE => {
+            let setting = lparam.0 as *const POWERBROADCAST_SETTING;
+            if let Some(display_state) =
+              unsafe { DisplayState::from_power_setting(setting) }
+            {
+              // the current state is sent on registration, which is not a change
+              let previous = self.data_lock().display_state.replace(display_state);
+              if previous.is_some_and(|previous| previous != display_state) {
+                self.send_message_to_main(Message::DisplayStateChanged(display_state));
+              }
+            }
+          }
+          _ => (),
+        }
+        LRESULT(1)
+      }
+      WindowsAndMessaging::WM_WTSSESSION_CHANGE => {
         match wparam.0 as u32 {
-          WindowsAndMessaging::SC_MINIMIZE => {
-            self.data.lock().unwrap().style.minimized = true;
+          WindowsAndMessaging::WTS_SESSION_LOCK => {
+            self.send_message_to_main(Message::SessionLocked);
           }
-          WindowsAndMessaging::SC_RESTORE => {
-            self.data.lock().unwrap().style.minimized = false;
+          WindowsAndMessaging::WTS_SESSION_UNLOCK => {
+            self.send_message_to_main(Message::SessionUnlocked);
           }
-          _ => {}
+          _ => (),
+        }
+        LRESULT(0)
+      }
+      WindowsAndMessaging::WM_CLIPBOARDUPDATE => {
+        self.send_message_to_main(Message::ClipboardChanged);
+        LRESULT(0)
+      }
+      WindowsAndMessaging::WM_COMMAND => {
+        // menus and accelerators have no control handle
+        let is_menu = lparam.0 == 0 && hi_word(wparam.0 as u32) <= 1;
+        if is_menu {
+          let id = MenuId(lo_word(wparam.0 as u32));
+          self.send_message_to_main(Message::MenuCommand(id));
+          return LRESULT(0);
         }
 
-        self.send_message_to_main(Message::SystemCommand);
+        self.send_message_to_main(Message::Command);
+        unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+      }
+      WindowsAndMessaging::WM_SYSCOMMAND => {
+        let command = SystemCommand::from_wparam(wparam);
+        self.send_message_to_main(Message::SystemCommand(command));
         unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
       }
       WindowsAndMessaging::WM_DPICHANGED => {
         let dpi = lo_word(wparam.0 as u32) as u32;
         let suggested_rect = unsafe { *(lparam.0 as *const RECT) };
-        unsafe {
+        let suggested_outer_size = PhysicalSize::new(
+          (suggested_rect.right - suggested_rect.left) as u32,
+          (suggested_rect.bottom - suggested_rect.top) as u32,
+        );
+        // the suggested rect includes the frame, which does not scale linearly
+        let frame = self.outer_size_for_dpi(PhysicalSize::default(), dpi);
+        let suggested_inner_size = PhysicalSize::new(
+          suggested_outer_size.width.saturating_sub(frame.width),
+          suggested_outer_size.height.saturating_sub(frame.height),
+        );
+
+        let scale_factor = dpi_to_scale_factor(dpi);
+        {
+          let mut data = self.data_lock();
+          data.scale_factor = scale_factor;
+          data.scale_factor_inner_size = Some(suggested_inner_size);
+        }
+        self.send_message_to_main(Message::ScaleFactorChanged {
+          scale_factor,
+          suggested_inner_size,
+        });
+
+        // the main thread may have replaced the size while handling the message
+        let inner_size = self
+          .data_lock()
+          .scale_factor_inner_size
+          .take()
+          .unwrap_or(suggested_inner_size);
+        let outer_size = self.outer_size_for_dpi(inner_size, dpi);
+        if let Err(e) = unsafe {
           SetWindowPos(
             hwnd,
             None,
             suggested_rect.left,
             suggested_rect.top,
-            suggested_rect.right - suggested_rect.left,
-            suggested_rect.bottom - suggested_rect.top,
+            outer_size.width as i32,
+            outer_size.height as i32,
             WindowsAndMessaging::SWP_NOZORDER | WindowsAndMessaging::SWP_NOACTIVATE,
           )
+        } {
+          tracing::error!("{e}");
         }
-        .unwrap();
-        let scale_factor = dpi_to_scale_factor(dpi);
-        self.data.lock().unwrap().scale_factor = scale_factor;
-        self.send_message_to_main(Message::ScaleFactorChanged(scale_factor));
-        unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+        LRESULT(0)
+      }
+      WindowsAndMessaging::WM_GETDPISCALEDSIZE => {
+        // scales the client area rather than the whole window, so that the logical inner
+        // size is kept across monitors
+        let dpi = wparam.0 as u32;
+        let ratio = dpi_to_scale_factor(dpi) / self.data_lock().scale_factor;
+        let mut client_rect = RECT::default();
+        let _ = unsafe { GetClientRect(hwnd, &mut client_rect) };
+        let inner_size = PhysicalSize::new(
+          ((client_rect.right - client_rect.left) as f64 * ratio).round() as u32,
+          ((client_rect.bottom - client_rect.top) as f64 * ratio).round() as u32,
+        );
+
+        let outer_size = self.outer_size_for_dpi(inner_size, dpi);
+        let size = unsafe { &mut *(lparam.0 as *mut SIZE) };
+        size.cx = outer_size.width as i32;
+        size.cy = outer_size.height as i32;
+        LRESULT(1)
       }
       WindowsAndMessaging::WM_INPUT => {
         let Some(data) = read_raw_input(HRAWINPUT(lparam.0)) else {
@@ -741,6 +1668,7 @@ impl Internal {
         }
         self.send_message_to_main(message);
         // messages.push();
+
         unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
       }
       WindowsAndMessaging::WM_MOUSEMOVE => {
@@ -783,13 +1711,15 @@ impl Internal {
 
               true
             }
-            CursorMoveKind::Inside => {
+            CursorMoveKind::Inside | CursorMoveKind::Outside => {
               self.data.lock().unwrap().cursor.last_position != position
             }
           }
         };
 
-        if send_message {
+        // the cursor is held in place, so its position is meaningless
+        let is_locked = self.data_lock().cursor.mode == CursorMode::Locked;
+        if send_message && !is_locked {
           self.send_message_to_main(Message::CursorMove { position, kind });
           self.data.lock().unwrap().cursor.last_position = position;
           if let Err(e) = self.refresh_os_cursor() {
@@ -810,22 +1740,21 @@ impl Internal {
         });
         unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
       }
-      WindowsAndMessaging::WM_MOUSEWHEEL => {
-        let delta = signed_hi_word(wparam.0 as i32) as f32
-          / WindowsAndMessaging::WHEEL_DELTA as f32;
-        self.send_message_to_main(Message::MouseWheel {
-          delta_x: 0.0,
-          delta_y: delta,
-        });
-        unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
-      }
-      WindowsAndMessaging::WM_MOUSEHWHEEL => {
-        let delta = signed_hi_word(wparam.0 as i32) as f32
-          / WindowsAndMessaging::WHEEL_DELTA as f32;
-        self.send_message_to_main(Message::MouseWheel {
-          delta_x: delta,
-          delta_y: 0.0,
-        });
+      WindowsAndMessaging::WM_MOUSEWHEEL | WindowsAndMessaging::WM_MOUSEHWHEEL => {
+        let delta = signed_hi_word(wparam.0 as i32);
+        let horizontal = msg == WindowsAndMessaging::WM_MOUSEHWHEEL;
+        let scrolled = {
+          let mut data = self.data_lock();
+          let scale_factor = data.scale_factor;
+          data.scroll.update(delta, horizontal, scale_factor)
+        };
+
+        if unsafe { SetTimer(hwnd, SCROLL_END_TIMER_ID, SCROLL_END_TIMEOUT, None) } == 0 {
+          tracing::error!("{}", windows::core::Error::from_win32());
+        }
+        if let Some((delta, phase)) = scrolled {
+          self.send_message_to_main(Message::MouseWheel { delta, phase });
+        }
         unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
       }
       msg
@@ -841,10 +1770,121 @@ impl Internal {
             .unwrap()
             .input
             .update_mouse_button_state(*button, *state);
+          self.refresh_mouse_capture(hwnd, MODIFIERKEYS_FLAGS(wparam.0 as u32));
         }
         self.send_message_to_main(message);
         unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
       }
+      WindowsAndMessaging::WM_CAPTURECHANGED => {
+        let was_captured = std::mem::replace(&mut self.data_lock().mouse_captured, false);
+        // another window took the capture, so the held buttons will not be released
+        if was_captured && HWND(lparam.0) != hwnd {
+          let (buttons, position) = {
+            let data = self.data_lock();
+            (data.input.pressed_mouse_buttons(), data.cursor.last_position)
+          };
+          for button in buttons {
+            self
+              .data_lock()
+              .input
+              .update_mouse_button_state(button, ButtonState::Released);
+            self.send_message_to_main(Message::MouseButton {
+              button,
+              state: ButtonState::Released,
+              position,
+              is_double_click: false,
+            });
+          }
+        }
+        LRESULT(0)
+      }
+      WindowsAndMessaging::WM_ENTERSIZEMOVE => {
+        // the modal loop only dispatches messages, so a timer is used to keep sending frames
+        if unsafe { SetTimer(hwnd, SIZE_MOVE_TIMER_ID, SIZE_MOVE_TIMER_INTERVAL, None) }
+          == 0
+        {
+          tracing::error!("{}", windows::core::Error::from_win32());
+        }
+        self.send_message_to_main(Message::SizeMoveStarted);
+        LRESULT(0)
+      }
+      WindowsAndMessaging::WM_EXITSIZEMOVE => {
+        let _ = unsafe { KillTimer(hwnd, SIZE_MOVE_TIMER_ID) };
+        self.send_message_to_main(Message::SizeMoveEnded);
+        LRESULT(0)
+      }
+      WindowsAndMessaging::WM_TIMER if wparam.0 == SIZE_MOVE_TIMER_ID => {
+        self.send_message_to_main(Message::Loop(LoopMessage::Empty));
+        LRESULT(0)
+      }
+      WindowsAndMessaging::WM_TIMER if wparam.0 == SCROLL_END_TIMER_ID => {
+        let _ = unsafe { KillTimer(hwnd, SCROLL_END_TIMER_ID) };
+        let ended = self.data_lock().scroll.end();
+        if let Some(delta) = ended {
+          self.send_message_to_main(Message::MouseWheel {
+            delta,
+            phase: ScrollPhase::Ended,
+          });
+        }
+        LRESULT(0)
+      }
+      WindowsAndMessaging::WM_TIMER if wparam.0 == CURSOR_ANIMATION_TIMER_ID => {
+        let inside_window = {
+          let mut data = self.data_lock();
+          data.cursor.frame = data.cursor.frame.wrapping_add(1);
+          data.cursor.inside_window
+        };
+        if inside_window {
+          self.show_cursor_icon();
+        }
+        LRESULT(0)
+      }
+      WindowsAndMessaging::WM_TIMER => {
+        let id = TimerId(wparam.0);
+        let repeat = self.data_lock().timers.get(&id).copied();
+        match repeat {
+          Some(repeat) => {
+            if !repeat {
+              self.data_lock().timers.remove(&id);
+              let _ = unsafe { KillTimer(hwnd, id.0) };
+            }
+            self.send_message_to_main(Message::Timer(id));
+            LRESULT(0)
+          }
+          None => unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) },
+        }
+      }
+      Tray::MESSAGE_ID => {
+        if let Some(event) = Tray::event(wparam.0, lparam.0) {
+          self.send_message_to_main(Message::Tray(event));
+        }
+
+        if let Some((x, y)) = Tray::context_menu_position(wparam.0, lparam.0) {
+          let menu = self
+            .data_lock()
+            .tray
+            .as_ref()
+            .and_then(|tray| tray.settings.menu.clone());
+          if let Some(menu) = menu {
+            // the menu only closes when clicking elsewhere if the window is in the foreground
+            unsafe { SetForegroundWindow(hwnd) };
+            if let Err(e) = menu.show_popup(hwnd, x, y) {
+              tracing::error!("{e}");
+            }
+            let _ =
+              unsafe { PostMessageW(hwnd, WindowsAndMessaging::WM_NULL, None, None) };
+          }
+        }
+        LRESULT(0)
+      }
+      msg if msg == taskbar_created_message() => {
+        if let Some(tray) = &self.data_lock().tray {
+          if let Err(e) = tray.restore(hwnd) {
+            tracing::error!("{e}");
+          }
+        }
+        unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+      }
       _ => unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) },
     }
   }
@@ -1313,7 +2353,57 @@ pub enum Fullscreen {
 pub enum CursorMode {
   #[default]
   Normal,
+  /// Confined to the client area while the window is focused.
   Confined,
+  /// Confined to part of the client area while the window is focused.
+  ConfinedTo(Rect),
+  /// Hidden and held at the center of the client area while the window is focused. Cursor
+  /// moves are not sent, so motion is only reported through raw input.
+  Locked,
+}
+
+/// Rectangle relative to the top-left corner of the client area.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rect {
+  pub position: PhysicalPosition,
+  pub size: PhysicalSize,
+}
+
+impl Rect {
+  pub fn new(position: PhysicalPosition, size: PhysicalSize) -> Self {
+    Self { position, size }
+  }
+}
+
+/// DPI awareness requested for the process when a window is created. The awareness can
+/// only be set once, so it is decided by the first window.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum DpiPolicy {
+  /// Scaled per monitor, including the title bar and menus. Falls back to
+  /// [`DpiPolicy::PerMonitor`] before Windows 10 1703.
+  #[default]
+  PerMonitorV2,
+  /// Scaled per monitor, without scaling the title bar or menus.
+  PerMonitor,
+  /// Scaled to the DPI of the primary monitor at login. Windows stretches the window on
+  /// monitors with another DPI.
+  System,
+  /// Always at 96 DPI. Windows stretches the window on monitors with another DPI.
+  Unaware,
+  /// Leaves the awareness unchanged, such as when it is set in the application manifest.
+  Unchanged,
+}
+
+/// When [`Message::RedrawRequested`] is sent after
+/// [`Window::request_redraw`](crate::Window::request_redraw).
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum RedrawPacing {
+  /// Sent as soon as the window thread handles the request.
+  #[default]
+  Immediate,
+  /// Sent after the next composition by the desktop window manager, which limits redraws
+  /// to the refresh rate of the display.
+  Compositor,
 }
 
 /// The wait behaviour of the window.
@@ -1342,3 +2432,185 @@ pub enum Theme {
   Dark,
   Light,
 }
+
+/// Material drawn by DWM behind the window frame. Only has an effect on Windows 11.
+///
+/// Any backdrop other than [`Backdrop::Auto`] and [`Backdrop::None`] extends the frame over the
+/// whole client area, so it only shows through wherever the window renders transparent pixels.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Backdrop {
+  /// Let DWM decide which backdrop to draw.
+  #[default]
+  Auto,
+  None,
+  /// Long-lived main windows.
+  Mica,
+  /// Transient windows such as popups and dialogs.
+  Acrylic,
+  /// Windows with tabbed title bars.
+  Tabbed,
+}
+
+/// Rounding applied by DWM to the corners of the window. Only has an effect on Windows 11.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CornerPreference {
+  #[default]
+  Default,
+  Round,
+  RoundSmall,
+  Square,
+}
+
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Color {
+  pub r: u8,
+  pub g: u8,
+  pub b: u8,
+}
+
+impl Color {
+  pub fn new(r: u8, g: u8, b: u8) -> Self {
+    Self { r, g, b }
+  }
+
+  /// Packs the color as a Win32 `COLORREF` (`0x00BBGGRR`).
+  pub fn as_colorref(&self) -> u32 {
+    self.r as u32 | (self.g as u32) << 8 | (self.b as u32) << 16
+  }
+}
+
+impl From<(u8, u8, u8)> for Color {
+  fn from(value: (u8, u8, u8)) -> Self {
+    Self {
+      r: value.0,
+      g: value.1,
+      b: value.2,
+    }
+  }
+}
+
+impl From<[u8; 3]> for Color {
+  fn from(value: [u8; 3]) -> Self {
+    Self {
+      r: value[0],
+      g: value[1],
+      b: value[2],
+    }
+  }
+}
+
+/// Z-order of the window relative to other windows.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum WindowLevel {
+  #[default]
+  Normal,
+  AlwaysOnTop,
+  AlwaysOnBottom,
+}
+
+/// How urgently the window should request the user's attention.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum UserAttention {
+  /// Flash the taskbar button until the window is focused.
+  Informational,
+  /// Flash both the caption and taskbar button until the window is focused.
+  Critical,
+}
+
+/// Identifier of a timer started with [`Window::set_timer`](super::Window::set_timer).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TimerId(pub(crate) usize);
+
+/// Show command for the first time the window is shown, from the minimized and maximized
+/// settings.
+pub(crate) fn initial_show_command(
+  minimized: bool,
+  maximized: bool,
+) -> Option<WindowsAndMessaging::SHOW_WINDOW_CMD> {
+  match (minimized, maximized) {
+    (true, _) => Some(WindowsAndMessaging::SW_SHOWMINIMIZED),
+    (false, true) => Some(WindowsAndMessaging::SW_SHOWMAXIMIZED),
+    (false, false) => None,
+  }
+}
+
+/// Show command for changing the visibility of the window. The initial show command is
+/// used if the window has not been shown yet, and the current state is kept otherwise.
+pub(crate) fn show_command(
+  visibility: Visibility,
+  initial_show: Option<WindowsAndMessaging::SHOW_WINDOW_CMD>,
+  minimized: bool,
+  maximized: bool,
+) -> WindowsAndMessaging::SHOW_WINDOW_CMD {
+  match (visibility, initial_show) {
+    (Visibility::Hidden, _) => WindowsAndMessaging::SW_HIDE,
+    (Visibility::Shown, Some(show)) => show,
+    (Visibility::Shown, None) if minimized => WindowsAndMessaging::SW_SHOWMINIMIZED,
+    (Visibility::Shown, None) if maximized => WindowsAndMessaging::SW_SHOWMAXIMIZED,
+    (Visibility::Shown, None) => WindowsAndMessaging::SW_SHOW,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn initial_show_command_from_settings() {
+    assert_eq!(initial_show_command(false, false), None);
+    assert_eq!(
+      initial_show_command(false, true),
+      Some(WindowsAndMessaging::SW_SHOWMAXIMIZED)
+    );
+    assert_eq!(
+      initial_show_command(true, false),
+      Some(WindowsAndMessaging::SW_SHOWMINIMIZED)
+    );
+    // minimized takes precedence, as the window restores to maximized
+    assert_eq!(
+      initial_show_command(true, true),
+      Some(WindowsAndMessaging::SW_SHOWMINIMIZED)
+    );
+  }
+
+  #[test]
+  fn first_show_uses_initial_command() {
+    // the style is neither minimized nor maximized before the window is first shown
+    let initial = initial_show_command(false, true);
+    assert_eq!(
+      show_command(Visibility::Shown, initial, false, false),
+      WindowsAndMessaging::SW_SHOWMAXIMIZED
+    );
+    let initial = initial_show_command(true, false);
+    assert_eq!(
+      show_command(Visibility::Shown, initial, false, false),
+      WindowsAndMessaging::SW_SHOWMINIMIZED
+    );
+  }
+
+  #[test]
+  fn later_shows_keep_current_state() {
+    assert_eq!(
+      show_command(Visibility::Shown, None, false, false),
+      WindowsAndMessaging::SW_SHOW
+    );
+    assert_eq!(
+      show_command(Visibility::Shown, None, true, false),
+      WindowsAndMessaging::SW_SHOWMINIMIZED
+    );
+    assert_eq!(
+      show_command(Visibility::Shown, None, false, true),
+      WindowsAndMessaging::SW_SHOWMAXIMIZED
+    );
+  }
+
+  #[test]
+  fn hiding_ignores_state() {
+    for initial in [None, Some(WindowsAndMessaging::SW_SHOWMAXIMIZED)] {
+      assert_eq!(
+        show_command(Visibility::Hidden, initial, false, true),
+        WindowsAndMessaging::SW_HIDE
+      );
+    }
+  }
+}
@@ -0,0 +1,291 @@
+use std::{
+  cell::Cell,
+  ffi::c_void,
+  path::PathBuf,
+  sync::{
+    atomic::{AtomicU32, Ordering},
+    Weak,
+  },
+};
+
+use windows::{
+  core::{IUnknown, IUnknown_Vtbl, Interface, GUID, HRESULT},
+  Win32::{
+    Foundation::*,
+    Graphics::Gdi::ScreenToClient,
+    System::{
+      Com::{IDataObject, DVASPECT_CONTENT, FORMATETC, TYMED_HGLOBAL},
+      DataExchange::RegisterClipboardFormatW,
+      Ole::{
+        IDropTarget,
+        IDropTarget_Vtbl,
+        RegisterDragDrop,
+        ReleaseStgMedium,
+        RevokeDragDrop,
+        CF_HDROP,
+        CF_UNICODETEXT,
+        DROPEFFECT,
+        DROPEFFECT_COPY,
+        DROPEFFECT_LINK,
+        DROPEFFECT_NONE,
+      },
+      SystemServices::MODIFIERKEYS_FLAGS,
+    },
+    UI::Shell::HDROP,
+  },
+};
+
+use super::{
+  data::{Internal, PhysicalPosition},
+  message::Message,
+};
+use crate::utilities::{hdrop_paths, read_wide_string};
+
+/// Contents of a drag-and-drop operation.
+enum Payload {
+  Files(Vec<PathBuf>),
+  Url(String),
+  Text(String),
+}
+
+/// Minimal OLE drop target forwarding drag-and-drop operations to the main thread.
+///
+/// OLE calls into this on the window thread while it is running the drag loop.
+#[repr(C)]
+pub(crate) struct DropTarget {
+  vtable: *const IDropTarget_Vtbl,
+  ref_count: AtomicU32,
+  state: Weak<Internal>,
+  hwnd: HWND,
+  effect: Cell<DROPEFFECT>,
+  hovering_files: Cell<bool>,
+}
+
+impl DropTarget {
+  const VTABLE: IDropTarget_Vtbl = IDropTarget_Vtbl {
+    base__: IUnknown_Vtbl {
+      QueryInterface: Self::query_interface,
+      AddRef: Self::add_ref,
+      Release: Self::release,
+    },
+    DragEnter: Self::drag_enter,
+    DragOver: Self::drag_over,
+    DragLeave: Self::drag_leave,
+    Drop: Self::drop_data,
+  };
+
+  /// Registers a new drop target for the window.
+  pub(crate) fn register(hwnd: HWND, state: Weak<Internal>) {
+    let target = Box::new(Self {
+      vtable: &Self::VTABLE,
+      ref_count: AtomicU32::new(1),
+      state,
+      hwnd,
+      effect: Cell::new(DROPEFFECT_NONE),
+      hovering_files: Cell::new(false),
+    });
+    // takes ownership of the initial reference
+    let target = unsafe { IDropTarget::from_raw(Box::into_raw(target) as *mut c_void) };
+
+    if let Err(e) = unsafe { RegisterDragDrop(hwnd, &target) } {
+      tracing::error!("failed to register drop target: {e}");
+    }
+  }
+
+  pub(crate) fn revoke(hwnd: HWND) {
+    let _ = unsafe { RevokeDragDrop(hwnd) };
+  }
+
+  unsafe fn from_this<'a>(this: *mut c_void) -> &'a Self {
+    unsafe { &*(this as *const Self) }
+  }
+
+  fn send_message_to_main(&self, message: Message) {
+    if let Some(state) = self.state.upgrade() {
+      if !state.is_closing() {
+        state.send_message_to_main(message);
+      }
+    }
+  }
+
+  fn client_position(&self, point: POINTL) -> PhysicalPosition {
+    let mut point = POINT {
+      x: point.x,
+      y: point.y,
+    };
+    unsafe { ScreenToClient(self.hwnd, &mut point) };
+    PhysicalPosition::new(point.x, point.y)
+  }
+
+  unsafe extern "system" fn query_interface(
+    this: *mut c_void,
+    iid: *const GUID,
+    interface: *mut *mut c_void,
+  ) -> HRESULT {
+    unsafe {
+      if interface.is_null() {
+        return E_POINTER;
+      }
+
+      if *iid == IUnknown::IID || *iid == IDropTarget::IID {
+        Self::add_ref(this);
+        *interface = this;
+        S_OK
+      } else {
+        *interface = std::ptr::null_mut();
+        E_NOINTERFACE
+      }
+    }
+  }
+
+  unsafe extern "system" fn add_ref(this: *mut c_void) -> u32 {
+    unsafe {
+      Self::from_this(this)
+        .ref_count
+        .fetch_add(1, Ordering::Relaxed)
+        + 1
+    }
+  }
+
+  unsafe extern "system" fn release(this: *mut c_void) -> u32 {
+    unsafe {
+      let count = Self::from_this(this)
+        .ref_count
+        .fetch_sub(1, Ordering::Release)
+        - 1;
+      if count == 0 {
+        std::sync::atomic::fence(Ordering::Acquire);
+        drop(Box::from_raw(this as *mut Self));
+      }
+      count
+    }
+  }
+
+  unsafe extern "system" fn drag_enter(
+    this: *mut c_void,
+    data: *mut c_void,
+    _key_state: MODIFIERKEYS_FLAGS,
+    _point: POINTL,
+    effect: *mut DROPEFFECT,
+  ) -> HRESULT {
+    unsafe {
+      let target = Self::from_this(this);
+      let payload = IDataObject::from_raw_borrowed(&data).and_then(read_payload);
+
+      target.effect.set(match &payload {
+        Some(Payload::Files(_) | Payload::Text(_)) => DROPEFFECT_COPY,
+        Some(Payload::Url(_)) => DROPEFFECT_LINK,
+        None => DROPEFFECT_NONE,
+      });
+      if let Some(effect) = effect.as_mut() {
+        *effect = target.effect.get();
+      }
+
+      if let Some(Payload::Files(paths)) = payload {
+        target.hovering_files.set(true);
+        for path in paths {
+          target.send_message_to_main(Message::FileHovered(path));
+        }
+      }
+
+      S_OK
+    }
+  }
+
+  unsafe extern "system" fn drag_over(
+    this: *mut c_void,
+    _key_state: MODIFIERKEYS_FLAGS,
+    _point: POINTL,
+    effect: *mut DROPEFFECT,
+  ) -> HRESULT {
+    unsafe {
+      if let Some(effect) = effect.as_mut() {
+        *effect = Self::from_this(this).effect.get();
+      }
+      S_OK
+    }
+  }
+
+  unsafe extern "system" fn drag_leave(this: *mut c_void) -> HRESULT {
+    unsafe {
+      let target = Self::from_this(this);
+      if target.hovering_files.replace(false) {
+        target.send_message_to_main(Message::FileHoverCancelled);
+      }
+      S_OK
+    }
+  }
+
+  unsafe extern "system" fn drop_data(
+    this: *mut c_void,
+    data: *mut c_void,
+    _key_state: MODIFIERKEYS_FLAGS,
+    point: POINTL,
+    effect: *mut DROPEFFECT,
+  ) -> HRESULT {
+    unsafe {
+      let target = Self::from_this(this);
+      target.hovering_files.set(false);
+
+      if let Some(effect) = effect.as_mut() {
+        *effect = target.effect.get();
+      }
+
+      let position = target.client_position(point);
+      match IDataObject::from_raw_borrowed(&data).and_then(read_payload) {
+        Some(Payload::Files(paths)) => {
+          target.send_message_to_main(Message::FileDropped { paths, position })
+        }
+        Some(Payload::Url(url)) => {
+          target.send_message_to_main(Message::UrlDropped { url, position })
+        }
+        Some(Payload::Text(text)) => {
+          target.send_message_to_main(Message::TextDropped { text, position })
+        }
+        None => (),
+      }
+
+      S_OK
+    }
+  }
+}
+
+/// Reads the most specific supported format from the data object.
+fn read_payload(data: &IDataObject) -> Option<Payload> {
+  if let Some(paths) =
+    read_hglobal(data, CF_HDROP.0, |hglobal| hdrop_paths(HDROP(hglobal.0 as isize)))
+  {
+    return Some(Payload::Files(paths));
+  }
+
+  let url_format =
+    unsafe { RegisterClipboardFormatW(windows::core::w!("UniformResourceLocatorW")) };
+  if let Some(url) =
+    read_hglobal(data, url_format as u16, |hglobal| unsafe { read_wide_string(hglobal) })
+  {
+    return Some(Payload::Url(url));
+  }
+
+  read_hglobal(data, CF_UNICODETEXT.0, |hglobal| unsafe { read_wide_string(hglobal) })
+    .map(Payload::Text)
+}
+
+fn read_hglobal<T>(
+  data: &IDataObject,
+  format: u16,
+  read: impl FnOnce(HGLOBAL) -> T,
+) -> Option<T> {
+  let format = FORMATETC {
+    cfFormat: format,
+    ptd: std::ptr::null_mut(),
+    dwAspect: DVASPECT_CONTENT.0,
+    lindex: -1,
+    tymed: TYMED_HGLOBAL.0 as u32,
+  };
+
+  let mut medium = unsafe { data.GetData(&format) }.ok()?;
+  let result = read(unsafe { medium.u.hGlobal });
+  unsafe { ReleaseStgMedium(&mut medium) };
+
+  Some(result)
+}
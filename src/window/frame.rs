@@ -1,4 +1,4 @@
-use crate::{Fullscreen, Visibility};
+use crate::{Fullscreen, Visibility, WindowLevel};
 
 #[derive(Debug, Clone)]
 pub struct Style {
@@ -6,6 +6,14 @@ pub struct Style {
   pub decorations: Visibility,
   pub fullscreen: Option<Fullscreen>,
   pub resizeable: bool,
+  pub transparent: bool,
+  pub opacity: f32,
+  pub cursor_hittest: bool,
+  pub level: WindowLevel,
+  pub skip_taskbar: bool,
+  pub tool_window: bool,
+  pub child: bool,
+  pub popup: bool,
   pub minimized: bool,
   pub maximized: bool,
   pub focused: bool,
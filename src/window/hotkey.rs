@@ -0,0 +1,188 @@
+use std::{
+  collections::BTreeMap,
+  sync::{Mutex, Weak},
+};
+
+use windows::Win32::{
+  Foundation::{HWND, LPARAM, WPARAM},
+  UI::{
+    Input::KeyboardAndMouse::{self, RegisterHotKey, UnregisterHotKey, VIRTUAL_KEY},
+    WindowsAndMessaging::{self, PostMessageW},
+  },
+};
+
+use super::{background, data::Internal, input::key::Key};
+use crate::error::WindowError;
+
+/// Windows that receive each registered hotkey, and the id to try next. Hotkeys are
+/// registered for the background window, so that registering never waits on a window
+/// thread, and forwarded from there.
+static TARGETS: Mutex<(BTreeMap<HotkeyId, HWND>, u16)> = Mutex::new((BTreeMap::new(), 0));
+
+/// Identifier sent back in [`Message::Hotkey`](super::message::Message::Hotkey) when a
+/// hotkey is pressed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HotkeyId(pub(crate) u16);
+
+impl HotkeyId {
+  /// Applications may only use ids below `0xC000`.
+  pub(crate) const MAX: u16 = 0xBFFF;
+}
+
+/// Key combination that is received from anywhere in the system, even while the window is
+/// not focused.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Hotkey {
+  pub key: Key,
+  pub ctrl: bool,
+  pub shift: bool,
+  pub alt: bool,
+  pub win: bool,
+  /// Whether holding the keys sends the hotkey repeatedly.
+  pub repeat: bool,
+}
+
+impl Hotkey {
+  pub fn new(key: Key) -> Self {
+    Self {
+      key,
+      ctrl: false,
+      shift: false,
+      alt: false,
+      win: false,
+      repeat: false,
+    }
+  }
+
+  pub fn with_ctrl(mut self, ctrl: bool) -> Self {
+    self.ctrl = ctrl;
+    self
+  }
+
+  pub fn with_shift(mut self, shift: bool) -> Self {
+    self.shift = shift;
+    self
+  }
+
+  pub fn with_alt(mut self, alt: bool) -> Self {
+    self.alt = alt;
+    self
+  }
+
+  /// Combinations with the Windows key are reserved for the system.
+  pub fn with_win(mut self, win: bool) -> Self {
+    self.win = win;
+    self
+  }
+
+  pub fn with_repeat(mut self, repeat: bool) -> Self {
+    self.repeat = repeat;
+    self
+  }
+
+  /// Registers the hotkey, forwarding it to the window as `WM_HOTKEY` once pressed.
+  pub(crate) fn register(&self, target: HWND) -> Result<HotkeyId, WindowError> {
+    let id = {
+      let (targets, next_id) = &mut *TARGETS.lock().unwrap();
+      let next = |id: u16| match id >= HotkeyId::MAX {
+        true => 0,
+        false => id + 1,
+      };
+      let mut id = *next_id;
+      while targets.contains_key(&HotkeyId(id)) {
+        id = next(id);
+        if id == *next_id {
+          return Err(WindowError::Error("no hotkey ids are left".to_owned()));
+        }
+      }
+      *next_id = next(id);
+      // reserved before registering, as the lock must not be held while waiting on the
+      // background thread
+      targets.insert(HotkeyId(id), target);
+      HotkeyId(id)
+    };
+
+    let mut modifiers = KeyboardAndMouse::HOT_KEY_MODIFIERS(0);
+    if self.ctrl {
+      modifiers |= KeyboardAndMouse::MOD_CONTROL;
+    }
+    if self.shift {
+      modifiers |= KeyboardAndMouse::MOD_SHIFT;
+    }
+    if self.alt {
+      modifiers |= KeyboardAndMouse::MOD_ALT;
+    }
+    if self.win {
+      modifiers |= KeyboardAndMouse::MOD_WIN;
+    }
+    if !self.repeat {
+      modifiers |= KeyboardAndMouse::MOD_NOREPEAT;
+    }
+
+    let key = VIRTUAL_KEY::from(self.key);
+    let registered = background::call(move |hwnd| unsafe {
+      RegisterHotKey(hwnd, id.0 as i32, modifiers, key.0 as u32)
+    });
+    match registered {
+      Ok(Ok(())) => Ok(id),
+      Ok(Err(e)) => {
+        TARGETS.lock().unwrap().0.remove(&id);
+        Err(e.into())
+      }
+      Err(e) => {
+        TARGETS.lock().unwrap().0.remove(&id);
+        Err(e)
+      }
+    }
+  }
+
+  pub(crate) fn unregister(id: HotkeyId) {
+    let _ = background::call(move |hwnd| unsafe { UnregisterHotKey(hwnd, id.0 as i32) });
+    TARGETS.lock().unwrap().0.remove(&id);
+  }
+}
+
+/// Forwards a `WM_HOTKEY` received by the background window to the window that registered
+/// the hotkey.
+pub(crate) fn forward(wparam: WPARAM, lparam: LPARAM) {
+  let target = u16::try_from(wparam.0 as i32)
+    .ok()
+    .and_then(|id| TARGETS.lock().unwrap().0.get(&HotkeyId(id)).copied());
+  if let Some(target) = target {
+    if let Err(e) =
+      unsafe { PostMessageW(target, WindowsAndMessaging::WM_HOTKEY, wparam, lparam) }
+    {
+      tracing::error!("{e}");
+    }
+  }
+}
+
+/// Keeps a hotkey registered until dropped or the window closes. Created with
+/// [`Window::register_hotkey`](super::Window::register_hotkey).
+#[derive(Debug)]
+#[must_use = "the hotkey is unregistered when dropped"]
+pub struct HotkeyRegistration {
+  window: Weak<Internal>,
+  id: HotkeyId,
+}
+
+impl HotkeyRegistration {
+  pub(crate) fn new(window: Weak<Internal>, id: HotkeyId) -> Self {
+    Self { window, id }
+  }
+
+  pub fn id(&self) -> HotkeyId {
+    self.id
+  }
+}
+
+impl Drop for HotkeyRegistration {
+  fn drop(&mut self) {
+    // the window unregisters its remaining hotkeys when it closes
+    if let Some(window) = self.window.upgrade() {
+      if window.data_lock().hotkeys.remove(&self.id).is_some() {
+        Hotkey::unregister(self.id);
+      }
+    }
+  }
+}
@@ -0,0 +1,139 @@
+use windows::Win32::{
+  Graphics::Gdi::{CreateBitmap, DeleteObject},
+  UI::WindowsAndMessaging::{
+    CreateIcon,
+    CreateIconIndirect,
+    DestroyIcon,
+    HCURSOR,
+    HICON,
+    ICONINFO,
+  },
+};
+
+use crate::{error::WindowError, PhysicalPosition};
+
+/// Icon image stored as tightly packed 8-bit RGBA rows from top to bottom.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Icon {
+  width: u32,
+  height: u32,
+  rgba: Vec<u8>,
+}
+
+impl Icon {
+  pub fn from_rgba(rgba: Vec<u8>, width: u32, height: u32) -> Result<Self, WindowError> {
+    let expected_len = width as usize * height as usize * 4;
+    if width == 0 || height == 0 || rgba.len() != expected_len {
+      return Err(WindowError::Error(format!(
+        "expected {expected_len} bytes of RGBA pixels for a {width}x{height} icon, found {}",
+        rgba.len()
+      )));
+    }
+
+    Ok(Self {
+      width,
+      height,
+      rgba,
+    })
+  }
+
+  pub fn width(&self) -> u32 {
+    self.width
+  }
+
+  pub fn height(&self) -> u32 {
+    self.height
+  }
+
+  pub fn rgba(&self) -> &[u8] {
+    &self.rgba
+  }
+
+  pub(crate) fn to_handle(&self) -> Result<IconHandle, WindowError> {
+    let bgra = self.bgra();
+    let mask = self.empty_mask();
+
+    let hicon = unsafe {
+      CreateIcon(
+        None,
+        self.width as i32,
+        self.height as i32,
+        1,
+        32,
+        mask.as_ptr(),
+        bgra.as_ptr(),
+      )
+    }?;
+
+    Ok(IconHandle(hicon))
+  }
+
+  /// Creates a cursor with the hotspot. The caller is responsible for destroying it.
+  pub(crate) fn to_cursor(
+    &self,
+    hotspot: PhysicalPosition,
+  ) -> Result<HCURSOR, WindowError> {
+    let bgra = self.bgra();
+    let mask = self.empty_mask();
+
+    let (color, mask) = unsafe {
+      (
+        CreateBitmap(
+          self.width as i32,
+          self.height as i32,
+          1,
+          32,
+          Some(bgra.as_ptr() as *const std::ffi::c_void),
+        ),
+        CreateBitmap(
+          self.width as i32,
+          self.height as i32,
+          1,
+          1,
+          Some(mask.as_ptr() as *const std::ffi::c_void),
+        ),
+      )
+    };
+
+    let hicon = unsafe {
+      CreateIconIndirect(&ICONINFO {
+        fIcon: false.into(),
+        xHotspot: hotspot.x as u32,
+        yHotspot: hotspot.y as u32,
+        hbmMask: mask,
+        hbmColor: color,
+      })
+    };
+    // the cursor keeps copies of the bitmaps
+    unsafe {
+      let _ = DeleteObject(color);
+      let _ = DeleteObject(mask);
+    }
+
+    Ok(HCURSOR(hicon?.0))
+  }
+
+  fn bgra(&self) -> Vec<u8> {
+    self
+      .rgba
+      .chunks_exact(4)
+      .flat_map(|pixel| [pixel[2], pixel[1], pixel[0], pixel[3]])
+      .collect()
+  }
+
+  /// Transparency comes from the alpha channel, so the mask is left empty. Rows of the
+  /// mask are padded to 16 bits.
+  fn empty_mask(&self) -> Vec<u8> {
+    vec![0u8; self.width.div_ceil(16) as usize * 2 * self.height as usize]
+  }
+}
+
+/// Icon created from an [`Icon`], destroyed on drop.
+#[derive(Debug)]
+pub(crate) struct IconHandle(pub HICON);
+
+impl Drop for IconHandle {
+  fn drop(&mut self) {
+    let _ = unsafe { DestroyIcon(self.0) };
+  }
+}
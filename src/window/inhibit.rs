@@ -0,0 +1,86 @@
+use std::sync::Weak;
+
+use windows::Win32::System::Power::{self, SetThreadExecutionState, EXECUTION_STATE};
+
+use super::{command::Command, data::Internal};
+
+/// When an [`Inhibitor`] applies.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum InhibitCondition {
+  #[default]
+  Always,
+  /// Only while the window has keyboard focus.
+  Focused,
+  /// Only while the window is fullscreen.
+  Fullscreen,
+}
+
+/// What an [`Inhibitor`] keeps awake.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct InhibitFlags {
+  /// Keeps the display from turning off or dimming, and the screensaver from starting.
+  pub display: bool,
+  /// Keeps the system from sleeping.
+  pub system: bool,
+  pub condition: InhibitCondition,
+}
+
+impl InhibitFlags {
+  pub fn new(display: bool, system: bool) -> Self {
+    Self {
+      display,
+      system,
+      condition: InhibitCondition::default(),
+    }
+  }
+
+  pub fn with_condition(mut self, condition: InhibitCondition) -> Self {
+    self.condition = condition;
+    self
+  }
+}
+
+/// Keeps the display or system awake until dropped or the window closes. Created with
+/// [`Window::inhibit`](super::Window::inhibit).
+#[derive(Debug)]
+#[must_use = "the inhibitor is released when dropped"]
+pub struct Inhibitor {
+  window: Weak<Internal>,
+  id: usize,
+}
+
+impl Inhibitor {
+  pub(crate) fn new(window: Weak<Internal>, id: usize) -> Self {
+    Self { window, id }
+  }
+}
+
+impl Drop for Inhibitor {
+  fn drop(&mut self) {
+    if let Some(window) = self.window.upgrade() {
+      if !window.is_closing() {
+        Command::Uninhibit(self.id).post(window.hwnd);
+      }
+    }
+  }
+}
+
+/// Applies the combined flags to the calling thread, which must be the window thread.
+/// The system releases them once the thread exits.
+pub(crate) fn set_execution_state<'a>(flags: impl IntoIterator<Item = &'a InhibitFlags>) {
+  let state = flags
+    .into_iter()
+    .fold(Power::ES_CONTINUOUS, |mut state, flags| {
+      if flags.display {
+        state |= Power::ES_DISPLAY_REQUIRED;
+      }
+      if flags.system {
+        state |= Power::ES_SYSTEM_REQUIRED;
+      }
+      state
+    });
+
+  if unsafe { SetThreadExecutionState(state) } == EXECUTION_STATE(0) {
+    tracing::error!("failed to set thread execution state");
+  }
+}
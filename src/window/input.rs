@@ -17,6 +17,7 @@ use crate::{
 };
 
 pub mod key;
+pub mod layout;
 pub mod mouse;
 pub mod state;
 
@@ -61,9 +62,7 @@ impl Input {
     button: MouseButton,
     new_state: ButtonState,
   ) {
-    if let Some(old_state) = self.mouse_buttons.get_mut(&button) {
-      *old_state = new_state;
-    }
+    self.mouse_buttons.insert(button, new_state);
   }
 
   pub fn update_modifiers_state(
@@ -135,6 +134,15 @@ impl Input {
       .unwrap_or(ButtonState::Released)
   }
 
+  pub(crate) fn pressed_mouse_buttons(&self) -> Vec<MouseButton> {
+    self
+      .mouse_buttons
+      .iter()
+      .filter(|(_, state)| state.is_pressed())
+      .map(|(button, _)| *button)
+      .collect()
+  }
+
   // MODS
 
   pub fn shift(&self) -> ButtonState {
@@ -463,6 +463,112 @@ impl From<Key> for VIRTUAL_KEY {
   }
 }
 
+/// Name of the key as printed on a US keyboard, such as in menu accelerator labels.
+impl std::fmt::Display for Key {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let name = match self {
+      Key::Unknown => "Unknown",
+      Key::Tab => "Tab",
+      Key::Enter => "Enter",
+      Key::Space => "Space",
+      Key::Escape => "Esc",
+      Key::NumEnter => "Num Enter",
+      Key::Backspace => "Backspace",
+      Key::Insert => "Ins",
+      Key::Delete => "Del",
+      Key::Up => "Up",
+      Key::Down => "Down",
+      Key::Left => "Left",
+      Key::Right => "Right",
+      Key::PageUp => "PgUp",
+      Key::PageDown => "PgDn",
+      Key::Home => "Home",
+      Key::End => "End",
+      Key::CapsLock => "Caps Lock",
+      Key::ScrollLock => "Scroll Lock",
+      Key::NumLock => "Num Lock",
+      Key::PrintScreen => "PrtSc",
+      Key::Pause => "Pause",
+      Key::Num0 => "Num 0",
+      Key::Num1 => "Num 1",
+      Key::Num2 => "Num 2",
+      Key::Num3 => "Num 3",
+      Key::Num4 => "Num 4",
+      Key::Num5 => "Num 5",
+      Key::Num6 => "Num 6",
+      Key::Num7 => "Num 7",
+      Key::Num8 => "Num 8",
+      Key::Num9 => "Num 9",
+      Key::F1 => "F1",
+      Key::F2 => "F2",
+      Key::F3 => "F3",
+      Key::F4 => "F4",
+      Key::F5 => "F5",
+      Key::F6 => "F6",
+      Key::F7 => "F7",
+      Key::F8 => "F8",
+      Key::F9 => "F9",
+      Key::F10 => "F10",
+      Key::F11 => "F11",
+      Key::F12 => "F12",
+      Key::F13 => "F13",
+      Key::F14 => "F14",
+      Key::F15 => "F15",
+      Key::F16 => "F16",
+      Key::F17 => "F17",
+      Key::F18 => "F18",
+      Key::F19 => "F19",
+      Key::F20 => "F20",
+      Key::F21 => "F21",
+      Key::F22 => "F22",
+      Key::F23 => "F23",
+      Key::F24 => "F24",
+      Key::NumPeriod => "Num .",
+      Key::NumComma => "Num ,",
+      Key::NumPlus => "Num +",
+      Key::NumMinus => "Num -",
+      Key::NumDivide => "Num /",
+      Key::NumMultiply => "Num *",
+      Key::NumEquals => "Num =",
+      Key::LeftShift | Key::RightShift => "Shift",
+      Key::LeftControl | Key::RightControl => "Ctrl",
+      Key::LeftAlt | Key::RightAlt => "Alt",
+      Key::LeftSuper | Key::RightSuper => "Win",
+      Key::Menu => "Menu",
+      Key::AbntC1 => "Abnt C1",
+      Key::AbntC2 => "Abnt C2",
+      Key::Ax => "AX",
+      Key::Convert => "Convert",
+      Key::Kana => "Kana",
+      Key::Kanji => "Kanji",
+      Key::Mail => "Mail",
+      Key::MediaPlayPause => "Play/Pause",
+      Key::MediaStop => "Stop",
+      Key::MediaSelect => "Media",
+      Key::MediaNextTrack => "Next Track",
+      Key::MediaPrevTrack => "Previous Track",
+      Key::VolumeDown => "Volume Down",
+      Key::VolumeUp => "Volume Up",
+      Key::VolumeMute => "Mute",
+      Key::NoConvert => "No Convert",
+      Key::OEM102 => "OEM 102",
+      Key::Sleep => "Sleep",
+      Key::NoName => "No Name",
+      Key::WebBack => "Browser Back",
+      Key::WebFavorites => "Browser Favorites",
+      Key::WebForward => "Browser Forward",
+      Key::WebHome => "Browser Home",
+      Key::WebRefresh => "Browser Refresh",
+      Key::WebSearch => "Browser Search",
+      Key::WebStop => "Browser Stop",
+      Key::Copy => "Copy",
+      // the remaining keys are ASCII and named by their character
+      key => return write!(f, "{}", *key as u8 as char),
+    };
+    f.write_str(name)
+  }
+}
+
 impl Key {
   /*
    Stolen from winit, under the Apache-2.0 license. See winit's license for more details.
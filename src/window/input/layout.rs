@@ -0,0 +1,107 @@
+use windows::Win32::{
+  Globalization::{LCIDToLocaleName, LOCALE_ALLOW_NEUTRAL_NAMES},
+  System::SystemServices::LOCALE_NAME_MAX_LENGTH,
+  UI::{
+    Input::KeyboardAndMouse::{
+      self,
+      GetKeyboardLayout,
+      MapVirtualKeyExW,
+      ToUnicodeEx,
+      VIRTUAL_KEY,
+    },
+    TextServices::HKL,
+  },
+};
+
+use super::key::Key;
+
+/// Keyboard layout and input language of the window, which decide the text produced by
+/// each key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyboardLayout {
+  /// Identifier of the layout (`HKL`). The low word is the language identifier.
+  pub layout_id: u32,
+  /// Name of the input language, such as `fr-FR`. Empty if it is unknown.
+  pub locale: String,
+}
+
+impl KeyboardLayout {
+  /// Layout of the calling thread.
+  pub(crate) fn current() -> Self {
+    Self::from_hkl(unsafe { GetKeyboardLayout(0) })
+  }
+
+  pub(crate) fn from_hkl(hkl: HKL) -> Self {
+    // identifiers only use the low 32 bits
+    let layout_id = hkl.0 as u32;
+    Self {
+      layout_id,
+      locale: locale_name(layout_id as u16),
+    }
+  }
+
+  pub fn language_id(&self) -> u16 {
+    self.layout_id as u16
+  }
+
+  /// Text typed by the key without modifiers in this layout, such as `é` for
+  /// [`Key::_2`] on a French layout. Dead keys return their accent, and keys that type
+  /// nothing, or only control characters, return `None`.
+  pub fn key_text(&self, key: Key) -> Option<String> {
+    let virtual_key = VIRTUAL_KEY::from(key).0 as u32;
+    let scan_code = unsafe {
+      MapVirtualKeyExW(virtual_key, KeyboardAndMouse::MAPVK_VK_TO_VSC, self.hkl())
+    };
+    self.text(virtual_key, scan_code)
+  }
+
+  /// Text typed by the physical key with the scan code, as sent with
+  /// [`Message::Key`](crate::Message::Key), without modifiers in this layout. For example,
+  /// the key left of `E` types `z` on a French layout and `w` on an English one.
+  pub fn scan_code_text(&self, scan_code: u16) -> Option<String> {
+    let virtual_key = unsafe {
+      MapVirtualKeyExW(scan_code as u32, KeyboardAndMouse::MAPVK_VSC_TO_VK_EX, self.hkl())
+    };
+    if virtual_key == 0 {
+      return None;
+    }
+    self.text(virtual_key, scan_code as u32)
+  }
+
+  fn text(&self, virtual_key: u32, scan_code: u32) -> Option<String> {
+    // keeps pending dead keys of the window thread from being consumed
+    const DONT_CHANGE_KEYBOARD_STATE: u32 = 1 << 2;
+
+    let state = [0u8; 256];
+    let mut buffer = [0u16; 8];
+    let len = unsafe {
+      ToUnicodeEx(
+        virtual_key,
+        scan_code,
+        &state,
+        &mut buffer,
+        DONT_CHANGE_KEYBOARD_STATE,
+        self.hkl(),
+      )
+    };
+    // negative for dead keys, which still write their character
+    let len = (len.unsigned_abs() as usize).min(buffer.len());
+    let text = String::from_utf16(&buffer[..len]).ok()?;
+    (!text.is_empty() && !text.chars().any(char::is_control)).then_some(text)
+  }
+
+  fn hkl(&self) -> HKL {
+    // sign extended like the original handle
+    HKL(self.layout_id as i32 as isize)
+  }
+}
+
+fn locale_name(language_id: u16) -> String {
+  let mut buffer = [0u16; LOCALE_NAME_MAX_LENGTH as usize];
+  let len = unsafe {
+    LCIDToLocaleName(language_id as u32, Some(&mut buffer), LOCALE_ALLOW_NEUTRAL_NAMES)
+  };
+  // the length includes the null terminator, and is zero on failure
+  let len = (len.max(1) as usize - 1).min(buffer.len());
+  String::from_utf16_lossy(&buffer[..len])
+}
@@ -1,6 +1,10 @@
 use windows::Win32::UI::WindowsAndMessaging;
 
 use super::state::ButtonState;
+use crate::utilities::wheel_scroll_setting;
+
+/// Logical pixels scrolled per line by high resolution devices.
+const PIXELS_PER_LINE: f32 = 20.0;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 #[repr(u16)]
@@ -55,3 +59,248 @@ pub(crate) fn mouse_button_states(flags: u16) -> [Option<ButtonState>; 5] {
     ),
   ]
 }
+
+/// Amount scrolled by [`Message::MouseWheel`](crate::Message::MouseWheel). Positive values
+/// scroll right and up, away from the user.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ScrollDelta {
+  /// Scrolled by a notched wheel, in whole lines or characters as set in the system scroll
+  /// settings.
+  Lines { x: f32, y: f32 },
+  /// Scrolled by a high resolution device such as a touchpad, in physical pixels.
+  Pixels { x: f32, y: f32 },
+}
+
+impl ScrollDelta {
+  fn new(precise: bool, x: f32, y: f32) -> Self {
+    match precise {
+      true => Self::Pixels { x, y },
+      false => Self::Lines { x, y },
+    }
+  }
+}
+
+/// Stage of a scroll gesture. Wheel messages carry no gestures, so a gesture ends once no
+/// scrolling has happened for a short while.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ScrollPhase {
+  Started,
+  Moved,
+  /// Sent with a zero delta.
+  Ended,
+}
+
+/// Turns wheel messages into scroll gestures.
+#[derive(Debug)]
+pub(crate) struct Scroll {
+  lines_per_notch: i32,
+  chars_per_notch: i32,
+  active: bool,
+  /// Whether the current gesture reports pixels. Decided by its first delta, so that a
+  /// gesture never switches units.
+  precise: bool,
+  /// Partial lines of each axis, in wheel units times lines per notch.
+  remainder: (i32, i32),
+}
+
+impl Scroll {
+  pub(crate) fn new() -> Self {
+    let mut scroll = Self {
+      lines_per_notch: 0,
+      chars_per_notch: 0,
+      active: false,
+      precise: false,
+      remainder: (0, 0),
+    };
+    scroll.refresh_settings();
+    scroll
+  }
+
+  pub(crate) fn refresh_settings(&mut self) {
+    self.lines_per_notch =
+      wheel_scroll_setting(WindowsAndMessaging::SPI_GETWHEELSCROLLLINES) as i32;
+    self.chars_per_notch =
+      wheel_scroll_setting(WindowsAndMessaging::SPI_GETWHEELSCROLLCHARS) as i32;
+  }
+
+  /// Adds the delta of a `WM_MOUSEWHEEL` or `WM_MOUSEHWHEEL` message. Returns nothing while
+  /// the deltas of a notched wheel add up to less than a line.
+  pub(crate) fn update(
+    &mut self,
+    delta: i16,
+    horizontal: bool,
+    scale_factor: f64,
+  ) -> Option<(ScrollDelta, ScrollPhase)> {
+    const NOTCH: i32 = WindowsAndMessaging::WHEEL_DELTA as i32;
+
+    let phase = match self.active {
+      true => ScrollPhase::Moved,
+      false => {
+        self.precise = delta as i32 % NOTCH != 0;
+        self.remainder = (0, 0);
+        ScrollPhase::Started
+      }
+    };
+
+    let (per_notch, remainder) = match horizontal {
+      true => (self.chars_per_notch, &mut self.remainder.0),
+      false => (self.lines_per_notch, &mut self.remainder.1),
+    };
+    let amount = match self.precise {
+      true => {
+        delta as f32 / NOTCH as f32
+          * per_notch as f32
+          * PIXELS_PER_LINE
+          * scale_factor as f32
+      }
+      false => {
+        *remainder += delta as i32 * per_notch;
+        let lines = *remainder / NOTCH;
+        *remainder -= lines * NOTCH;
+        lines as f32
+      }
+    };
+    if amount == 0.0 {
+      return None;
+    }
+
+    self.active = true;
+    let delta = match horizontal {
+      true => ScrollDelta::new(self.precise, amount, 0.0),
+      false => ScrollDelta::new(self.precise, 0.0, amount),
+    };
+    Some((delta, phase))
+  }
+
+  /// Ends the current gesture, returning the delta to send with [`ScrollPhase::Ended`].
+  pub(crate) fn end(&mut self) -> Option<ScrollDelta> {
+    std::mem::replace(&mut self.active, false)
+      .then(|| ScrollDelta::new(self.precise, 0.0, 0.0))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn scroll_with(lines_per_notch: i32, chars_per_notch: i32) -> Scroll {
+    Scroll {
+      lines_per_notch,
+      chars_per_notch,
+      active: false,
+      precise: false,
+      remainder: (0, 0),
+    }
+  }
+
+  fn lines(y: f32) -> ScrollDelta {
+    ScrollDelta::Lines { x: 0.0, y }
+  }
+
+  #[test]
+  fn notches_scroll_lines() {
+    let mut scroll = scroll_with(3, 2);
+    assert_eq!(scroll.update(120, false, 1.0), Some((lines(3.0), ScrollPhase::Started)));
+    assert_eq!(scroll.update(240, false, 1.0), Some((lines(6.0), ScrollPhase::Moved)));
+    assert_eq!(
+      scroll.update(120, true, 1.0),
+      Some((ScrollDelta::Lines { x: 2.0, y: 0.0 }, ScrollPhase::Moved))
+    );
+  }
+
+  #[test]
+  fn partial_notches_add_up_to_a_line() {
+    let mut scroll = scroll_with(1, 1);
+    assert_eq!(scroll.update(120, false, 1.0), Some((lines(1.0), ScrollPhase::Started)));
+    assert_eq!(scroll.update(40, false, 1.0), None);
+    assert_eq!(scroll.update(40, false, 1.0), None);
+    assert_eq!(scroll.update(40, false, 1.0), Some((lines(1.0), ScrollPhase::Moved)));
+  }
+
+  #[test]
+  fn remainder_carries_over() {
+    let mut scroll = scroll_with(1, 1);
+    assert_eq!(scroll.update(120, false, 1.0), Some((lines(1.0), ScrollPhase::Started)));
+    assert_eq!(scroll.update(60, false, 1.0), None);
+    // 160 units, leaving 40
+    assert_eq!(scroll.update(100, false, 1.0), Some((lines(1.0), ScrollPhase::Moved)));
+    assert_eq!(scroll.update(80, false, 1.0), Some((lines(1.0), ScrollPhase::Moved)));
+    assert_eq!(scroll.update(80, false, 1.0), None);
+  }
+
+  #[test]
+  fn axes_keep_separate_remainders() {
+    let mut scroll = scroll_with(1, 1);
+    assert!(scroll.update(120, false, 1.0).is_some());
+    assert_eq!(scroll.update(60, false, 1.0), None);
+    assert_eq!(scroll.update(60, true, 1.0), None);
+    assert_eq!(scroll.update(60, false, 1.0), Some((lines(1.0), ScrollPhase::Moved)));
+  }
+
+  #[test]
+  fn negative_deltas() {
+    let mut scroll = scroll_with(3, 3);
+    assert_eq!(
+      scroll.update(-120, false, 1.0),
+      Some((lines(-3.0), ScrollPhase::Started))
+    );
+
+    let mut scroll = scroll_with(1, 1);
+    assert!(scroll.update(-120, false, 1.0).is_some());
+    assert_eq!(scroll.update(-40, false, 1.0), None);
+    assert_eq!(scroll.update(-80, false, 1.0), Some((lines(-1.0), ScrollPhase::Moved)));
+    // reversing direction cancels the partial line
+    assert_eq!(scroll.update(-60, false, 1.0), None);
+    assert_eq!(scroll.update(60, false, 1.0), None);
+    assert_eq!(scroll.update(-60, false, 1.0), None);
+    assert_eq!(scroll.update(-60, false, 1.0), Some((lines(-1.0), ScrollPhase::Moved)));
+  }
+
+  #[test]
+  fn first_delta_chooses_pixels() {
+    let mut scroll = scroll_with(3, 3);
+    // a quarter notch at 3 lines of 20 pixels
+    assert_eq!(
+      scroll.update(30, false, 1.0),
+      Some((ScrollDelta::Pixels { x: 0.0, y: 15.0 }, ScrollPhase::Started))
+    );
+    // whole notches stay in pixels for the rest of the gesture
+    assert_eq!(
+      scroll.update(120, false, 2.0),
+      Some((ScrollDelta::Pixels { x: 0.0, y: 120.0 }, ScrollPhase::Moved))
+    );
+    assert_eq!(scroll.end(), Some(ScrollDelta::Pixels { x: 0.0, y: 0.0 }));
+
+    // the next gesture decides again
+    assert_eq!(scroll.update(120, false, 1.0), Some((lines(3.0), ScrollPhase::Started)));
+  }
+
+  #[test]
+  fn first_delta_chooses_lines() {
+    let mut scroll = scroll_with(3, 3);
+    assert_eq!(scroll.update(120, false, 1.0), Some((lines(3.0), ScrollPhase::Started)));
+    // partial notches stay in lines for the rest of the gesture
+    assert_eq!(scroll.update(40, false, 1.0), Some((lines(1.0), ScrollPhase::Moved)));
+    assert_eq!(scroll.end(), Some(lines(0.0)));
+  }
+
+  #[test]
+  fn end_without_gesture() {
+    let mut scroll = scroll_with(1, 1);
+    assert_eq!(scroll.end(), None);
+
+    // deltas that scroll nothing do not start a gesture
+    let mut scroll = scroll_with(0, 0);
+    assert_eq!(scroll.update(120, false, 1.0), None);
+    assert_eq!(scroll.end(), None);
+  }
+
+  #[test]
+  fn end_finishes_gesture() {
+    let mut scroll = scroll_with(1, 1);
+    assert!(scroll.update(120, false, 1.0).is_some());
+    assert_eq!(scroll.end(), Some(lines(0.0)));
+    assert_eq!(scroll.end(), None);
+    assert_eq!(scroll.update(120, false, 1.0), Some((lines(1.0), ScrollPhase::Started)));
+  }
+}
@@ -0,0 +1,382 @@
+use std::cell::Cell;
+
+use windows::{
+  core::{HSTRING, PWSTR},
+  Win32::{
+    Foundation::HWND,
+    UI::{
+      Input::KeyboardAndMouse::VIRTUAL_KEY,
+      WindowsAndMessaging::{
+        self,
+        CreateAcceleratorTableW,
+        CreateMenu,
+        CreatePopupMenu,
+        DestroyAcceleratorTable,
+        DestroyMenu,
+        InsertMenuItemW,
+        TrackPopupMenuEx,
+        TranslateAcceleratorW,
+        ACCEL,
+        HACCEL,
+        HMENU,
+        MENUITEMINFOW,
+        MSG,
+      },
+    },
+  },
+};
+
+use super::input::key::Key;
+use crate::error::WindowError;
+
+/// Identifier sent back in [`Message::MenuCommand`](super::message::Message::MenuCommand)
+/// when a menu item is selected.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MenuId(pub u16);
+
+impl From<u16> for MenuId {
+  fn from(value: u16) -> Self {
+    Self(value)
+  }
+}
+
+/// Key combination that selects a menu item while the window is focused.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Accelerator {
+  pub key: Key,
+  pub ctrl: bool,
+  pub shift: bool,
+  pub alt: bool,
+}
+
+impl Accelerator {
+  pub fn new(key: Key) -> Self {
+    Self {
+      key,
+      ctrl: false,
+      shift: false,
+      alt: false,
+    }
+  }
+
+  pub fn with_ctrl(mut self, ctrl: bool) -> Self {
+    self.ctrl = ctrl;
+    self
+  }
+
+  pub fn with_shift(mut self, shift: bool) -> Self {
+    self.shift = shift;
+    self
+  }
+
+  pub fn with_alt(mut self, alt: bool) -> Self {
+    self.alt = alt;
+    self
+  }
+
+  /// Text shown next to the item, such as `Ctrl+Shift+S`.
+  pub fn label(&self) -> String {
+    let mut label = String::new();
+    if self.ctrl {
+      label.push_str("Ctrl+");
+    }
+    if self.shift {
+      label.push_str("Shift+");
+    }
+    if self.alt {
+      label.push_str("Alt+");
+    }
+    label.push_str(&self.key.to_string());
+    label
+  }
+
+  fn to_accel(self, id: MenuId) -> ACCEL {
+    let mut flags = WindowsAndMessaging::FVIRTKEY;
+    if self.ctrl {
+      flags |= WindowsAndMessaging::FCONTROL;
+    }
+    if self.shift {
+      flags |= WindowsAndMessaging::FSHIFT;
+    }
+    if self.alt {
+      flags |= WindowsAndMessaging::FALT;
+    }
+    ACCEL {
+      fVirt: flags,
+      key: VIRTUAL_KEY::from(self.key).0,
+      cmd: id.0,
+    }
+  }
+}
+
+thread_local! {
+  /// Accelerator table of the window owned by the current thread.
+  static ACCELERATORS: Cell<Option<(HWND, HACCEL)>> = const { Cell::new(None) };
+}
+
+/// Accelerator table translated by the message pump of the window, destroyed on drop.
+pub(crate) struct AcceleratorTable(HACCEL);
+
+impl AcceleratorTable {
+  /// Makes the message pump of the calling thread translate the table for the window. Must
+  /// be called on the thread of the window.
+  pub(crate) fn activate(&self, hwnd: HWND) {
+    ACCELERATORS.set(Some((hwnd, self.0)));
+  }
+}
+
+impl Drop for AcceleratorTable {
+  fn drop(&mut self) {
+    ACCELERATORS.with(|accelerators| {
+      if accelerators
+        .get()
+        .is_some_and(|(_, haccel)| haccel == self.0)
+      {
+        accelerators.set(None);
+      }
+    });
+    let _ = unsafe { DestroyAcceleratorTable(self.0) };
+  }
+}
+
+/// Sends the accelerated menu command of a key message to the window of the calling
+/// thread. Returns whether the message was translated and must not be dispatched.
+pub(crate) fn translate_accelerator(msg: &MSG) -> bool {
+  match ACCELERATORS.get() {
+    Some((hwnd, haccel)) => (unsafe { TranslateAcceleratorW(hwnd, haccel, msg) }) != 0,
+    None => false,
+  }
+}
+
+/// Check mark shown next to a menu item.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MenuCheck {
+  Check(bool),
+  /// Shown as a bullet. Checking one radio item does not uncheck the others.
+  Radio(bool),
+}
+
+/// Selectable item in a [`Menu`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MenuItem {
+  pub id: MenuId,
+  pub label: String,
+  pub enabled: bool,
+  pub check: Option<MenuCheck>,
+  pub accelerator: Option<Accelerator>,
+}
+
+impl MenuItem {
+  pub fn new(id: impl Into<MenuId>, label: impl Into<String>) -> Self {
+    Self {
+      id: id.into(),
+      label: label.into(),
+      enabled: true,
+      check: None,
+      accelerator: None,
+    }
+  }
+
+  pub fn with_enabled(mut self, enabled: bool) -> Self {
+    self.enabled = enabled;
+    self
+  }
+
+  pub fn with_check(mut self, check: Option<MenuCheck>) -> Self {
+    self.check = check;
+    self
+  }
+
+  /// Only applies while the menu is set as the menu bar of the window.
+  pub fn with_accelerator(mut self, accelerator: Option<Accelerator>) -> Self {
+    self.accelerator = accelerator;
+    self
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MenuEntry {
+  Item(MenuItem),
+  Submenu { label: String, menu: Menu },
+  Separator,
+}
+
+/// Menu bar or context menu.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Menu {
+  pub entries: Vec<MenuEntry>,
+}
+
+impl Menu {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_item(mut self, item: MenuItem) -> Self {
+    self.entries.push(MenuEntry::Item(item));
+    self
+  }
+
+  pub fn with_submenu(mut self, label: impl Into<String>, menu: Menu) -> Self {
+    self.entries.push(MenuEntry::Submenu {
+      label: label.into(),
+      menu,
+    });
+    self
+  }
+
+  pub fn with_separator(mut self) -> Self {
+    self.entries.push(MenuEntry::Separator);
+    self
+  }
+
+  /// Finds the item with the id, searching submenus.
+  pub fn item(&self, id: MenuId) -> Option<&MenuItem> {
+    self.entries.iter().find_map(|entry| match entry {
+      MenuEntry::Item(item) if item.id == id => Some(item),
+      MenuEntry::Submenu { menu, .. } => menu.item(id),
+      _ => None,
+    })
+  }
+
+  /// Finds the item with the id, searching submenus.
+  pub fn item_mut(&mut self, id: MenuId) -> Option<&mut MenuItem> {
+    self.entries.iter_mut().find_map(|entry| match entry {
+      MenuEntry::Item(item) if item.id == id => Some(item),
+      MenuEntry::Submenu { menu, .. } => menu.item_mut(id),
+      _ => None,
+    })
+  }
+
+  /// Creates the accelerator table for the enabled items, searching submenus. Returns
+  /// [`None`] if no item has an accelerator.
+  pub(crate) fn accelerator_table(
+    &self,
+  ) -> Result<Option<AcceleratorTable>, WindowError> {
+    let mut accels = Vec::new();
+    self.collect_accels(&mut accels);
+    if accels.is_empty() {
+      return Ok(None);
+    }
+    let haccel = unsafe { CreateAcceleratorTableW(&accels) }?;
+    Ok(Some(AcceleratorTable(haccel)))
+  }
+
+  fn collect_accels(&self, accels: &mut Vec<ACCEL>) {
+    for entry in &self.entries {
+      match entry {
+        MenuEntry::Item(MenuItem {
+          id,
+          enabled: true,
+          accelerator: Some(accelerator),
+          ..
+        }) => accels.push(accelerator.to_accel(*id)),
+        MenuEntry::Submenu { menu, .. } => menu.collect_accels(accels),
+        _ => (),
+      }
+    }
+  }
+
+  /// Creates the native menu. The caller is responsible for destroying it unless it is
+  /// attached to a window.
+  pub(crate) fn build(&self, popup: bool) -> Result<HMENU, WindowError> {
+    let hmenu = match popup {
+      true => unsafe { CreatePopupMenu() }?,
+      false => unsafe { CreateMenu() }?,
+    };
+
+    if let Err(e) = self.append_entries(hmenu) {
+      let _ = unsafe { DestroyMenu(hmenu) };
+      return Err(e);
+    }
+
+    Ok(hmenu)
+  }
+
+  /// Shows the menu at the point in screen coordinates until it is closed. The selected
+  /// item is sent to the window as `WM_COMMAND`.
+  pub(crate) fn show_popup(&self, hwnd: HWND, x: i32, y: i32) -> Result<(), WindowError> {
+    let hmenu = self.build(true)?;
+    unsafe {
+      TrackPopupMenuEx(hmenu, WindowsAndMessaging::TPM_RIGHTBUTTON.0, x, y, hwnd, None);
+      let _ = DestroyMenu(hmenu);
+    }
+    Ok(())
+  }
+
+  fn append_entries(&self, hmenu: HMENU) -> Result<(), WindowError> {
+    for (position, entry) in self.entries.iter().enumerate() {
+      let mut info = MENUITEMINFOW {
+        cbSize: std::mem::size_of::<MENUITEMINFOW>() as u32,
+        fMask: WindowsAndMessaging::MIIM_FTYPE,
+        ..Default::default()
+      };
+
+      // must outlive the call to insert the item
+      let label: HSTRING;
+      match entry {
+        MenuEntry::Item(item) => {
+          label = match &item.accelerator {
+            Some(accelerator) => format!("{}\t{}", item.label, accelerator.label()),
+            None => item.label.clone(),
+          }
+          .into();
+          info.fMask |= WindowsAndMessaging::MIIM_ID
+            | WindowsAndMessaging::MIIM_STRING
+            | WindowsAndMessaging::MIIM_STATE;
+          info.wID = item.id.0 as u32;
+          info.dwTypeData = PWSTR(label.as_ptr() as *mut u16);
+          if !item.enabled {
+            info.fState |= WindowsAndMessaging::MFS_DISABLED;
+          }
+          match item.check {
+            Some(MenuCheck::Check(checked)) if checked => {
+              info.fState |= WindowsAndMessaging::MFS_CHECKED;
+            }
+            Some(MenuCheck::Radio(checked)) => {
+              info.fType |= WindowsAndMessaging::MFT_RADIOCHECK;
+              if checked {
+                info.fState |= WindowsAndMessaging::MFS_CHECKED;
+              }
+            }
+            _ => (),
+          }
+        }
+        MenuEntry::Submenu { label: text, menu } => {
+          label = text.into();
+          info.fMask |=
+            WindowsAndMessaging::MIIM_STRING | WindowsAndMessaging::MIIM_SUBMENU;
+          info.dwTypeData = PWSTR(label.as_ptr() as *mut u16);
+          // destroyed along with the parent menu
+          info.hSubMenu = menu.build(true)?;
+        }
+        MenuEntry::Separator => {
+          info.fType |= WindowsAndMessaging::MFT_SEPARATOR;
+        }
+      }
+
+      unsafe { InsertMenuItemW(hmenu, position as u32, true, &info) }?;
+    }
+
+    Ok(())
+  }
+}
+
+/// Replaces the menu bar of the window, destroying the previous one.
+pub(crate) fn set_window_menu(
+  hwnd: HWND,
+  menu: Option<&Menu>,
+) -> Result<(), WindowError> {
+  let hmenu = match menu {
+    Some(menu) => menu.build(false)?,
+    None => HMENU::default(),
+  };
+
+  let previous = unsafe { WindowsAndMessaging::GetMenu(hwnd) };
+  unsafe { WindowsAndMessaging::SetMenu(hwnd, hmenu) }?;
+  if !previous.is_invalid() {
+    let _ = unsafe { DestroyMenu(previous) };
+  }
+
+  Ok(())
+}
@@ -1,12 +1,19 @@
+use std::path::PathBuf;
+
 use windows::Win32::{
   Foundation::{HINSTANCE, HWND, LPARAM, RECT, WPARAM},
-  System::SystemServices::{
-    MK_LBUTTON,
-    MK_MBUTTON,
-    MK_RBUTTON,
-    MK_XBUTTON1,
-    MK_XBUTTON2,
-    MODIFIERKEYS_FLAGS,
+  Graphics::Gdi,
+  System::{
+    Power::POWERBROADCAST_SETTING,
+    SystemServices::{
+      GUID_CONSOLE_DISPLAY_STATE,
+      MK_LBUTTON,
+      MK_MBUTTON,
+      MK_RBUTTON,
+      MK_XBUTTON1,
+      MK_XBUTTON2,
+      MODIFIERKEYS_FLAGS,
+    },
   },
   UI::{
     Input::KeyboardAndMouse::{MapVirtualKeyW, MAPVK_VSC_TO_VK_EX, VIRTUAL_KEY},
@@ -16,8 +23,14 @@ use windows::Win32::{
 
 use super::{
   command::Command,
-  data::{PhysicalPosition, PhysicalSize},
-  input::{mouse::MouseButton, state::RawKeyState},
+  data::{Color, PhysicalPosition, PhysicalSize, Theme, TimerId},
+  hotkey::HotkeyId,
+  input::{
+    mouse::{MouseButton, ScrollDelta, ScrollPhase},
+    state::RawKeyState,
+  },
+  menu::MenuId,
+  tray::TrayEvent,
 };
 use crate::{
   utilities::{hi_word, is_flag_set, lo_byte, lo_word, signed_hi_word, signed_lo_word},
@@ -33,6 +46,86 @@ pub enum Focus {
   Lost,
 }
 
+/// Action requested through the window menu, title bar buttons, or keyboard shortcuts.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SystemCommand {
+  Close,
+  Minimize,
+  Maximize,
+  Restore,
+  Move,
+  Size,
+  /// Window menu opened with the keyboard.
+  KeyMenu,
+  /// Window menu opened with the mouse.
+  MouseMenu,
+  NextWindow,
+  PrevWindow,
+  TaskList,
+  ScreenSave,
+  MonitorPower,
+  ContextHelp,
+  HotKey,
+  /// Any other `SC_*` value.
+  Other(u32),
+}
+
+impl SystemCommand {
+  pub(crate) fn from_wparam(wparam: WPARAM) -> Self {
+    // the low four bits are used internally by Windows
+    match wparam.0 as u32 & 0xFFF0 {
+      WindowsAndMessaging::SC_CLOSE => Self::Close,
+      WindowsAndMessaging::SC_MINIMIZE => Self::Minimize,
+      WindowsAndMessaging::SC_MAXIMIZE => Self::Maximize,
+      WindowsAndMessaging::SC_RESTORE => Self::Restore,
+      WindowsAndMessaging::SC_MOVE => Self::Move,
+      WindowsAndMessaging::SC_SIZE => Self::Size,
+      WindowsAndMessaging::SC_KEYMENU => Self::KeyMenu,
+      WindowsAndMessaging::SC_MOUSEMENU => Self::MouseMenu,
+      WindowsAndMessaging::SC_NEXTWINDOW => Self::NextWindow,
+      WindowsAndMessaging::SC_PREVWINDOW => Self::PrevWindow,
+      WindowsAndMessaging::SC_TASKLIST => Self::TaskList,
+      Gdi::SC_SCREENSAVE => Self::ScreenSave,
+      WindowsAndMessaging::SC_MONITORPOWER => Self::MonitorPower,
+      WindowsAndMessaging::SC_CONTEXTHELP => Self::ContextHelp,
+      WindowsAndMessaging::SC_HOTKEY => Self::HotKey,
+      other => Self::Other(other),
+    }
+  }
+}
+
+/// Power state of the display the session is shown on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DisplayState {
+  Off,
+  On,
+  Dimmed,
+}
+
+impl DisplayState {
+  /// Decodes a `GUID_CONSOLE_DISPLAY_STATE` power setting notification.
+  pub(crate) unsafe fn from_power_setting(
+    setting: *const POWERBROADCAST_SETTING,
+  ) -> Option<Self> {
+    let setting_ref = unsafe { setting.as_ref() }?;
+    if setting_ref.PowerSetting != GUID_CONSOLE_DISPLAY_STATE
+      || (setting_ref.DataLength as usize) < std::mem::size_of::<u32>()
+    {
+      return None;
+    }
+
+    // the data extends past the end of the declared struct
+    let value =
+      unsafe { (std::ptr::addr_of!((*setting).Data) as *const u32).read_unaligned() };
+    match value {
+      0 => Some(Self::Off),
+      1 => Some(Self::On),
+      2 => Some(Self::Dimmed),
+      _ => None,
+    }
+  }
+}
+
 /// Messages sent by the window, message loop, or attached devices.
 #[derive(Debug, PartialEq, Clone)]
 pub enum Message {
@@ -46,6 +139,10 @@ pub enum Message {
   CloseRequested,
   /// Message sent when Windows requests the window be repainted.
   Paint,
+  /// Message sent when a redraw requested with
+  /// [`Window::request_redraw`](super::Window::request_redraw) is due. Unlike [`Paint`],
+  /// this means the contents of the window are stale.
+  RedrawRequested,
   /// Message sent when a key is pressed, held, or released.
   Key {
     key: Key,
@@ -61,15 +158,20 @@ pub enum Message {
     alt: ButtonState,
     win: ButtonState,
   },
-  /// Message sent when a mouse button is pressed or released.
+  /// Message sent when a mouse button is pressed or released. While the mouse is captured,
+  /// the position may lie outside of the client area, and moves outside of it are sent as
+  /// [`CursorMove`] with [`CursorMoveKind::Outside`].
   MouseButton {
     button: MouseButton,
     state: ButtonState,
     position: PhysicalPosition,
     is_double_click: bool,
   },
-  /// Message sent when the scroll wheel is actuated.
-  MouseWheel { delta_x: f32, delta_y: f32 },
+  /// Message sent when the scroll wheel or a touchpad is scrolled.
+  MouseWheel {
+    delta: ScrollDelta,
+    phase: ScrollPhase,
+  },
   /// Message sent when the cursor is moved within the window bounds. Don't
   /// use this for mouse input in cases such as first-person cameras as it is
   /// locked to the bounds of the window.
@@ -81,19 +183,101 @@ pub enum Message {
   Resized(PhysicalSize),
   /// Message sent when the window is moved. Sent after [`BoundsChanged`]
   Moved(PhysicalPosition),
+  /// Message sent when the user starts dragging the border or title bar of the window.
+  /// Until [`SizeMoveEnded`] is sent, [`LoopMessage::Empty`] is also sent about every
+  /// 16 milliseconds regardless of [`Flow`](super::data::Flow), so rendering can keep up
+  /// with the drag.
+  SizeMoveStarted,
+  /// Message sent when the user releases the border or title bar of the window.
+  SizeMoveEnded,
   /// Message sent first when the window is moved or resized.
   BoundsChanged {
     outer_position: PhysicalPosition,
     outer_size: PhysicalSize,
   },
-  /// Message sent by Windows when certain actions are taken. WIP
+  /// Message sent by Windows when a control sends a notification. WIP
   Command,
-  /// Message sent by Windows when certain actions are taken. WIP
-  SystemCommand,
+  /// Message sent when a menu item is selected, either by clicking it or through its
+  /// accelerator.
+  MenuCommand(MenuId),
+  /// Message sent when an action is chosen from the window menu or title bar.
+  SystemCommand(SystemCommand),
   /// Message sent when the window gains or loses focus.
   Focus(Focus),
-  /// Message sent when the scale factor of the window has changed.
-  ScaleFactorChanged(f64),
+  /// Message sent when the window is minimized, maximized, restored, or enters or leaves
+  /// fullscreen.
+  StateChanged {
+    minimized: bool,
+    maximized: bool,
+    fullscreen: bool,
+  },
+  /// Message sent when the window stops or starts being visible on screen, such as when it
+  /// is hidden, minimized, or on another virtual desktop. Windows covered by other windows
+  /// are not considered occluded.
+  Occluded(bool),
+  /// Message sent when the scale factor of the window has changed, such as when it is
+  /// moved to another monitor. The window is resized to `suggested_inner_size`, which
+  /// keeps its logical inner size, unless another size is set with
+  /// [`Window::set_scale_factor_inner_size`](super::Window::set_scale_factor_inner_size)
+  /// while handling this message.
+  ScaleFactorChanged {
+    scale_factor: f64,
+    suggested_inner_size: PhysicalSize,
+  },
+  /// Message sent when the system switches between light and dark mode while the window
+  /// uses [`Theme::Auto`]. Contains the newly applied theme.
+  ThemeChanged(Theme),
+  /// Message sent when the system accent color changes.
+  AccentColorChanged(Color),
+  /// Message sent when high contrast mode is turned on or off.
+  HighContrastChanged(bool),
+  /// Message sent for each file dragged over the window. Requires drag-and-drop to be
+  /// enabled.
+  FileHovered(PathBuf),
+  /// Message sent when files dragged over the window leave it without being dropped.
+  FileHoverCancelled,
+  /// Message sent when files are dropped onto the window.
+  FileDropped {
+    paths: Vec<PathBuf>,
+    position: PhysicalPosition,
+  },
+  /// Message sent when text is dropped onto the window.
+  TextDropped {
+    text: String,
+    position: PhysicalPosition,
+  },
+  /// Message sent when a link, such as one dragged out of a browser, is dropped onto the
+  /// window.
+  UrlDropped {
+    url: String,
+    position: PhysicalPosition,
+  },
+  /// Message sent when the contents of the system clipboard change.
+  ClipboardChanged,
+  /// Message sent by the tray icon of the window.
+  Tray(TrayEvent),
+  /// Message sent when the system is about to suspend. Requires power events to be
+  /// enabled.
+  Suspended,
+  /// Message sent when the system resumes from suspend. GPU resources may have been lost.
+  /// Requires power events to be enabled.
+  Resumed,
+  /// Message sent when the workstation is locked. Requires power events to be enabled.
+  SessionLocked,
+  /// Message sent when the workstation is unlocked. Requires power events to be enabled.
+  SessionUnlocked,
+  /// Message sent when the display is turned off, on, or dimmed. Requires power events to
+  /// be enabled.
+  DisplayStateChanged(DisplayState),
+  /// Message sent when a timer started with [`Window::set_timer`](super::Window::set_timer)
+  /// expires.
+  Timer(TimerId),
+  /// Message sent when a hotkey registered with
+  /// [`Window::register_hotkey`](super::Window::register_hotkey) is pressed.
+  Hotkey(HotkeyId),
+  /// Message sent when the keyboard layout or input language of the window changes. See
+  /// [`KeyboardLayout`](super::input::layout::KeyboardLayout).
+  KeyboardLayoutChanged { layout_id: u32, locale: String },
 }
 
 /// Artificial window messages sent by the window loop.
@@ -288,6 +472,9 @@ pub enum CursorMoveKind {
   Left,
   /// Cursor is inside the window or `GetClientRect` failed.
   Inside,
+  /// Cursor moved outside of the client area after leaving it, which is only reported while
+  /// the mouse is captured.
+  Outside,
 }
 
 pub(crate) fn get_cursor_move_kind(
@@ -311,6 +498,8 @@ pub(crate) fn get_cursor_move_kind(
     CursorMoveKind::Entered
   } else if mouse_was_inside_window && !(x && y) {
     CursorMoveKind::Left
+  } else if !(x && y) {
+    CursorMoveKind::Outside
   } else {
     CursorMoveKind::Inside
   }
@@ -0,0 +1,294 @@
+use windows::Win32::Graphics::Gdi::{
+  self,
+  ExcludeClipRect,
+  PatBlt,
+  RestoreDC,
+  SaveDC,
+  SetStretchBltMode,
+  StretchDIBits,
+  BITMAPINFO,
+  BITMAPINFOHEADER,
+  HDC,
+};
+
+use super::data::{PhysicalPosition, PhysicalSize};
+
+/// Byte order of the pixels passed to [`Window::present`](super::Window::present). Each
+/// `u32` holds one pixel, read as little-endian bytes.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PixelFormat {
+  /// `0xAABBGGRR` when read as a `u32`.
+  Rgba,
+  /// `0xAARRGGBB` when read as a `u32`, which is the native format of Windows bitmaps.
+  #[default]
+  Bgra,
+}
+
+impl PixelFormat {
+  /// Converts a pixel to the native `0xAARRGGBB` format.
+  ///
+  /// ```
+  /// # use witer::window::present::PixelFormat;
+  /// assert_eq!(PixelFormat::Rgba.to_bgra(0xFF33_2211), 0xFF11_2233);
+  /// assert_eq!(PixelFormat::Bgra.to_bgra(0xFF33_2211), 0xFF33_2211);
+  /// ```
+  pub fn to_bgra(self, pixel: u32) -> u32 {
+    match self {
+      Self::Rgba => (pixel & 0xFF00_FF00) | (pixel & 0xFF) << 16 | (pixel >> 16) & 0xFF,
+      Self::Bgra => pixel,
+    }
+  }
+
+  /// Converts the pixels to the native format, replacing the contents of `dst`.
+  pub fn convert(self, src: &[u32], dst: &mut Vec<u32>) {
+    dst.clear();
+    match self {
+      Self::Rgba => dst.extend(src.iter().map(|&pixel| self.to_bgra(pixel))),
+      Self::Bgra => dst.extend_from_slice(src),
+    }
+  }
+}
+
+/// How a frame is scaled to the client area. Uncovered parts of the client area are
+/// filled with black.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Scaling {
+  /// Scaled by the largest whole factor that fits, and centered. Frames larger than the
+  /// client area are shown unscaled and cropped.
+  Integer,
+  /// Scaled to fit while preserving the aspect ratio, and centered.
+  #[default]
+  Fit,
+  /// Stretched to cover the client area.
+  Stretch,
+}
+
+impl Scaling {
+  /// Returns the position and size of the frame within the client area.
+  ///
+  /// ```
+  /// # use witer::{window::present::Scaling, PhysicalPosition, PhysicalSize};
+  /// let frame = PhysicalSize::new(320, 240);
+  /// let client = PhysicalSize::new(1000, 600);
+  ///
+  /// assert_eq!(
+  ///   Scaling::Stretch.destination(frame, client),
+  ///   (PhysicalPosition::new(0, 0), PhysicalSize::new(1000, 600))
+  /// );
+  /// assert_eq!(
+  ///   Scaling::Fit.destination(frame, client),
+  ///   (PhysicalPosition::new(100, 0), PhysicalSize::new(800, 600))
+  /// );
+  /// assert_eq!(
+  ///   Scaling::Integer.destination(frame, client),
+  ///   (PhysicalPosition::new(180, 60), PhysicalSize::new(640, 480))
+  /// );
+  /// ```
+  pub fn destination(
+    self,
+    frame: PhysicalSize,
+    client: PhysicalSize,
+  ) -> (PhysicalPosition, PhysicalSize) {
+    if frame.is_any_zero() || client.is_any_zero() {
+      return (PhysicalPosition::default(), PhysicalSize::default());
+    }
+
+    let (frame_width, frame_height) = (frame.width as u64, frame.height as u64);
+    let (client_width, client_height) = (client.width as u64, client.height as u64);
+    let (width, height) = match self {
+      Self::Integer => {
+        let factor = (client_width / frame_width)
+          .min(client_height / frame_height)
+          .max(1);
+        (frame_width * factor, frame_height * factor)
+      }
+      // compares the aspect ratios without dividing
+      Self::Fit if client_width * frame_height <= client_height * frame_width => {
+        (client_width, (frame_height * client_width + frame_width / 2) / frame_width)
+      }
+      Self::Fit => {
+        ((frame_width * client_height + frame_height / 2) / frame_height, client_height)
+      }
+      Self::Stretch => (client_width, client_height),
+    };
+
+    let position = PhysicalPosition::new(
+      ((client_width as i64 - width as i64) / 2) as i32,
+      ((client_height as i64 - height as i64) / 2) as i32,
+    );
+    (position, PhysicalSize::new(width as u32, height as u32))
+  }
+}
+
+/// Last frame presented to the window, kept to repaint the window.
+#[derive(Debug, Default)]
+pub(crate) struct Framebuffer {
+  front: Vec<u32>,
+  /// Reused for the next frame to avoid reallocating.
+  back: Vec<u32>,
+  size: PhysicalSize,
+  scaling: Scaling,
+}
+
+impl Framebuffer {
+  /// Converts the pixels into the back buffer, then swaps it to the front.
+  pub(crate) fn swap(
+    &mut self,
+    pixels: &[u32],
+    size: PhysicalSize,
+    format: PixelFormat,
+    scaling: Scaling,
+  ) {
+    format.convert(pixels, &mut self.back);
+    std::mem::swap(&mut self.front, &mut self.back);
+    self.size = size;
+    self.scaling = scaling;
+  }
+
+  /// Draws the front buffer to the device context of a client area of the given size.
+  pub(crate) fn draw(&self, hdc: HDC, client: PhysicalSize) {
+    let (position, size) = self.scaling.destination(self.size, client);
+    let info = BITMAPINFO {
+      bmiHeader: BITMAPINFOHEADER {
+        biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+        biWidth: self.size.width as i32,
+        // top-down
+        biHeight: -(self.size.height as i32),
+        biPlanes: 1,
+        biBitCount: 32,
+        biCompression: Gdi::BI_RGB.0,
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+
+    unsafe {
+      // the window class uses its own device context, so changes must be undone
+      let saved = SaveDC(hdc);
+      SetStretchBltMode(hdc, Gdi::COLORONCOLOR);
+      StretchDIBits(
+        hdc,
+        position.x,
+        position.y,
+        size.width as i32,
+        size.height as i32,
+        0,
+        0,
+        self.size.width as i32,
+        self.size.height as i32,
+        Some(self.front.as_ptr() as *const std::ffi::c_void),
+        &info,
+        Gdi::DIB_RGB_COLORS,
+        Gdi::SRCCOPY,
+      );
+      ExcludeClipRect(
+        hdc,
+        position.x,
+        position.y,
+        position.x + size.width as i32,
+        position.y + size.height as i32,
+      );
+      let _ =
+        PatBlt(hdc, 0, 0, client.width as i32, client.height as i32, Gdi::BLACKNESS);
+      let _ = RestoreDC(hdc, saved);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn to_bgra() {
+    assert_eq!(PixelFormat::Rgba.to_bgra(0x8033_2211), 0x8011_2233);
+    assert_eq!(PixelFormat::Rgba.to_bgra(0x00FF_00FF), 0x00FF_00FF);
+    assert_eq!(PixelFormat::Bgra.to_bgra(0x8033_2211), 0x8033_2211);
+  }
+
+  #[test]
+  fn convert() {
+    let src = [0xFF33_2211, 0x0000_00FF, 0x00FF_0000];
+    let mut dst = vec![0xDEAD_BEEF; 8];
+
+    PixelFormat::Rgba.convert(&src, &mut dst);
+    assert_eq!(dst, [0xFF11_2233, 0x00FF_0000, 0x0000_00FF]);
+
+    PixelFormat::Bgra.convert(&src, &mut dst);
+    assert_eq!(dst, src);
+  }
+
+  fn destination(
+    scaling: Scaling,
+    frame: (u32, u32),
+    client: (u32, u32),
+  ) -> ((i32, i32), (u32, u32)) {
+    let (position, size) = scaling.destination(
+      PhysicalSize::new(frame.0, frame.1),
+      PhysicalSize::new(client.0, client.1),
+    );
+    ((position.x, position.y), (size.width, size.height))
+  }
+
+  #[test]
+  fn integer_scaling() {
+    assert_eq!(
+      destination(Scaling::Integer, (320, 240), (1000, 600)),
+      ((180, 60), (640, 480))
+    );
+    // limited by the smaller factor
+    assert_eq!(
+      destination(Scaling::Integer, (100, 50), (350, 120)),
+      ((75, 10), (200, 100))
+    );
+    assert_eq!(
+      destination(Scaling::Integer, (320, 240), (320, 240)),
+      ((0, 0), (320, 240))
+    );
+  }
+
+  #[test]
+  fn integer_scaling_crops_larger_frames() {
+    assert_eq!(
+      destination(Scaling::Integer, (800, 600), (400, 300)),
+      ((-200, -150), (800, 600))
+    );
+    assert_eq!(
+      destination(Scaling::Integer, (800, 100), (400, 300)),
+      ((-200, 100), (800, 100))
+    );
+  }
+
+  #[test]
+  fn fit_scaling() {
+    // client is wider than the frame, so bars are left and right
+    assert_eq!(
+      destination(Scaling::Fit, (320, 240), (1000, 600)),
+      ((100, 0), (800, 600))
+    );
+    // client is taller than the frame, so bars are above and below
+    assert_eq!(destination(Scaling::Fit, (320, 240), (640, 720)), ((0, 120), (640, 480)));
+    // rounded to the nearest pixel
+    assert_eq!(destination(Scaling::Fit, (3, 2), (10, 10)), ((0, 1), (10, 7)));
+    assert_eq!(destination(Scaling::Fit, (800, 600), (400, 300)), ((0, 0), (400, 300)));
+  }
+
+  #[test]
+  fn stretch_scaling() {
+    assert_eq!(
+      destination(Scaling::Stretch, (320, 240), (1000, 600)),
+      ((0, 0), (1000, 600))
+    );
+    assert_eq!(destination(Scaling::Stretch, (800, 600), (400, 30)), ((0, 0), (400, 30)));
+  }
+
+  #[test]
+  fn zero_sizes() {
+    for scaling in [Scaling::Integer, Scaling::Fit, Scaling::Stretch] {
+      assert_eq!(destination(scaling, (0, 240), (1000, 600)), ((0, 0), (0, 0)));
+      assert_eq!(destination(scaling, (320, 0), (1000, 600)), ((0, 0), (0, 0)));
+      assert_eq!(destination(scaling, (320, 240), (0, 600)), ((0, 0), (0, 0)));
+      assert_eq!(destination(scaling, (320, 240), (1000, 0)), ((0, 0), (0, 0)));
+    }
+  }
+}
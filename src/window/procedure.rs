@@ -1,10 +1,24 @@
-use std::sync::{Arc, Mutex};
+use std::{
+  collections::VecDeque,
+  sync::{Arc, Mutex},
+};
 
-use cursor_icon::CursorIcon;
 // use crossbeam::channel::{Receiver, Sender};
 use windows::Win32::{
   Foundation::*,
+  System::{
+    DataExchange::{AddClipboardFormatListener, RemoveClipboardFormatListener},
+    Power::{RegisterPowerSettingNotification, UnregisterPowerSettingNotification},
+    RemoteDesktop::{
+      WTSRegisterSessionNotification,
+      WTSUnRegisterSessionNotification,
+      NOTIFY_FOR_THIS_SESSION,
+    },
+    SystemServices::GUID_CONSOLE_DISPLAY_STATE,
+    Threading::GetCurrentThreadId,
+  },
   UI::{
+    Accessibility::{SetWinEventHook, UnhookWinEvent, HWINEVENTHOOK},
     HiDpi::EnableNonClientDpiScaling,
     WindowsAndMessaging::{
       self,
@@ -24,7 +38,7 @@ use super::{
   command::Command,
   data::{Data, Position, Size, SyncData, Visibility},
   frame::Style,
-  settings::WindowSettings,
+  settings::{Parent, WindowSettings},
   Window,
 };
 use crate::{
@@ -32,12 +46,19 @@ use crate::{
   utilities::{
     dpi_to_scale_factor,
     hwnd_dpi,
+    is_high_contrast_enabled,
     register_all_mice_and_keyboards_for_raw_input,
+    system_accent_color,
   },
   window::{
-    cursor::Cursor,
-    data::{Internal, PhysicalPosition},
+    cursor::{Cursor, CursorImage},
+    data::{initial_show_command, Internal, PhysicalPosition},
+    drop_target::DropTarget,
+    hotkey::Hotkey,
+    input::{layout::KeyboardLayout, mouse::Scroll},
     stage::Stage,
+    suppress,
+    tray::Tray,
   },
   LoopMessage,
 };
@@ -46,10 +67,11 @@ pub struct CreateInfo {
   pub title: String,
   pub size: Size,
   pub position: Option<Position>,
+  pub(crate) parent: Option<Parent>,
   pub settings: WindowSettings,
   pub class_atom: u16,
   pub window: Option<Window>,
-  pub message: Arc<Mutex<Option<Message>>>,
+  pub message: Arc<Mutex<VecDeque<Message>>>,
   pub sync: SyncData,
   pub style: Style,
 }
@@ -86,6 +108,10 @@ pub extern "system" fn wnd_proc(
         }
       }
       WindowsAndMessaging::WM_DESTROY => {
+        DropTarget::revoke(hwnd);
+        let _ = unsafe { RemoveClipboardFormatListener(hwnd) };
+        Tray::remove(hwnd);
+        let _ = unsafe { WTSUnRegisterSessionNotification(hwnd) };
         unsafe { PostQuitMessage(0) };
         unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
       }
@@ -98,6 +124,21 @@ pub extern "system" fn wnd_proc(
           match command {
             Command::Exit => {
               let user_data = unsafe { Box::from_raw(state_ptr as *mut UserData) };
+              user_data.state.end_modal();
+              if let Some(handle) =
+                user_data.state.data_lock().display_notification.take()
+              {
+                let _ = unsafe { UnregisterPowerSettingNotification(handle) };
+              }
+              if let Some(hook) = user_data.state.data_lock().occlusion_hook.take() {
+                let _ = unsafe { UnhookWinEvent(hook) };
+              }
+              suppress::apply(hwnd, None);
+              for id in
+                std::mem::take(&mut user_data.state.data_lock().hotkeys).into_keys()
+              {
+                Hotkey::unregister(id);
+              }
               user_data
                 .state
                 .send_message_to_main(Message::Loop(LoopMessage::Exit));
@@ -127,6 +168,37 @@ pub extern "system" fn wnd_proc(
   }
 }
 
+/// Receives cloaking events for windows of the window thread, delivered through its
+/// message loop.
+unsafe extern "system" fn win_event_proc(
+  _hook: HWINEVENTHOOK,
+  _event: u32,
+  hwnd: HWND,
+  object: i32,
+  child: i32,
+  _thread: u32,
+  _time: u32,
+) {
+  if object != WindowsAndMessaging::OBJID_WINDOW.0
+    || child != WindowsAndMessaging::CHILDID_SELF as i32
+  {
+    return;
+  }
+
+  // other windows, such as those created by OLE, may live on the same thread
+  let wnd_proc_ptr =
+    unsafe { GetWindowLongPtrW(hwnd, WindowsAndMessaging::GWLP_WNDPROC) };
+  if wnd_proc_ptr != wnd_proc as *const () as isize {
+    return;
+  }
+
+  let user_data_ptr =
+    unsafe { GetWindowLongPtrW(hwnd, WindowsAndMessaging::GWLP_USERDATA) };
+  if let Some(user_data) = unsafe { (user_data_ptr as *mut UserData).as_ref() } {
+    user_data.state.refresh_occlusion(hwnd);
+  }
+}
+
 fn on_nccreate(hwnd: HWND, msg: u32, w_param: WPARAM, l_param: LPARAM) -> LRESULT {
   if let Err(e) = unsafe { EnableNonClientDpiScaling(hwnd) } {
     tracing::error!("{e}");
@@ -155,21 +227,39 @@ fn on_create(hwnd: HWND, msg: u32, w_param: WPARAM, l_param: LPARAM) -> LRESULT
     .into(),
   );
 
+  let owner = create_info
+    .parent
+    .as_ref()
+    .map(|parent| parent.window().clone());
+  let modal =
+    create_info.settings.modal && matches!(create_info.parent, Some(Parent::Owner(_)));
+
   // create state
   let input = Input::new();
   let state = Arc::new(Internal {
     hinstance: create_struct.hInstance,
     hwnd,
     class_atom: create_info.class_atom,
+    owner,
     message: create_info.message.clone(),
     sync: create_info.sync.clone(),
     thread: Mutex::new(None),
+    framebuffer: Mutex::new(None),
     data: Mutex::new(Data {
       title: create_info.title.clone(),
       subtitle: Default::default(),
       theme: Default::default(),
+      requested_theme: Default::default(),
+      accent_color: system_accent_color(),
+      high_contrast: is_high_contrast_enabled(),
+      backdrop: Default::default(),
+      corner_preference: Default::default(),
+      border_color: None,
+      caption_color: None,
+      caption_text_color: None,
       style: create_info.style.clone(),
       scale_factor,
+      scale_factor_inner_size: None,
       last_windowed_position: position,
       last_windowed_size: size,
       cursor: Cursor {
@@ -177,13 +267,40 @@ fn on_create(hwnd: HWND, msg: u32, w_param: WPARAM, l_param: LPARAM) -> LRESULT
         visibility: Visibility::Shown,
         inside_window: false,
         last_position: PhysicalPosition::default(),
-        selected_icon: CursorIcon::Default,
+        selected_icon: CursorImage::default(),
+        frame: 0,
       },
       flow: create_info.settings.flow,
       close_on_x: create_info.settings.close_on_x,
       stage: Stage::Setup,
       input,
+      modal,
+      mouse_capture: create_info.settings.mouse_capture,
+      mouse_captured: false,
+      scroll: Scroll::new(),
+      key_suppression: create_info.settings.key_suppression,
+      keyboard_layout: KeyboardLayout::current(),
+      menu: None,
+      accelerators: None,
+      tray: None,
+      power_events: create_info.settings.power_events,
+      display_state: None,
+      display_notification: None,
+      // the window is created hidden
+      occluded: true,
+      occlusion_hook: None,
+      hotkeys: Default::default(),
+      next_inhibitor_id: 0,
+      inhibitors: Default::default(),
+      next_timer_id: 1,
+      timers: Default::default(),
+      reported_state: (false, false, create_info.style.fullscreen.is_some()),
+      initial_show: initial_show_command(
+        create_info.settings.minimized,
+        create_info.settings.maximized,
+      ),
       requested_redraw: false,
+      redraw_pacing: create_info.settings.redraw_pacing,
     }),
   });
 
@@ -200,14 +317,79 @@ fn on_create(hwnd: HWND, msg: u32, w_param: WPARAM, l_param: LPARAM) -> LRESULT
 
   let window = Window(state.clone());
   window.force_set_theme(create_info.settings.theme);
+  window.force_set_backdrop(create_info.settings.backdrop);
+  window.force_set_corner_preference(create_info.settings.corner_preference);
+  window.force_set_border_color(create_info.settings.border_color);
+  window.force_set_caption_color(create_info.settings.caption_color);
+  window.force_set_caption_text_color(create_info.settings.caption_text_color);
 
   if let Some(position) = create_info.position {
     Command::SetPosition(position).send(hwnd);
   }
+  if let Some(menu) = &create_info.settings.menu {
+    Command::SetMenu(Some(menu.clone())).send(hwnd);
+  }
   Command::SetSize(size).send(hwnd);
   Command::SetDecorations(create_info.settings.decorations).send(hwnd);
+  if create_info.settings.transparent {
+    Command::SetTransparent(true).send(hwnd);
+  }
+  Command::SetOpacity(create_info.settings.opacity).send(hwnd);
   Command::SetVisibility(create_info.settings.visibility).send(hwnd);
   Command::SetFullscreen(create_info.settings.fullscreen).send(hwnd);
+  Command::SetWindowLevel(create_info.settings.window_level).send(hwnd);
+
+  if let Err(e) = unsafe { AddClipboardFormatListener(hwnd) } {
+    tracing::error!("{e}");
+  }
+
+  if let Some(tray_icon) = &create_info.settings.tray_icon {
+    Command::SetTrayIcon(Some(tray_icon.clone())).send(hwnd);
+  }
+
+  if create_info.settings.power_events {
+    if let Err(e) =
+      unsafe { WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION) }
+    {
+      tracing::error!("{e}");
+    }
+    match unsafe {
+      RegisterPowerSettingNotification(
+        HANDLE(hwnd.0),
+        &GUID_CONSOLE_DISPLAY_STATE,
+        WindowsAndMessaging::DEVICE_NOTIFY_WINDOW_HANDLE,
+      )
+    } {
+      Ok(handle) => state.data_lock().display_notification = Some(handle),
+      Err(e) => tracing::error!("{e}"),
+    }
+  }
+
+  let occlusion_hook = unsafe {
+    SetWinEventHook(
+      WindowsAndMessaging::EVENT_OBJECT_CLOAKED,
+      WindowsAndMessaging::EVENT_OBJECT_UNCLOAKED,
+      HMODULE::default(),
+      Some(win_event_proc),
+      0,
+      GetCurrentThreadId(),
+      WindowsAndMessaging::WINEVENT_OUTOFCONTEXT,
+    )
+  };
+  if occlusion_hook.is_invalid() {
+    tracing::error!("failed to hook cloaking events");
+  } else {
+    state.data_lock().occlusion_hook = Some(occlusion_hook);
+  }
+
+  if create_info.settings.drag_and_drop {
+    DropTarget::register(hwnd, Arc::downgrade(&state));
+  }
+
+  if let (true, Some(owner)) = (modal, &window.0.owner) {
+    // Posted so that the thread of the owner is never waited on from here
+    Command::SetEnabled(false).post(owner.0.hwnd);
+  }
 
   tracing::trace!("[`{}`]: window is ready", create_info.title);
   window.0.data.lock().unwrap().stage = Stage::Ready;
@@ -219,7 +401,7 @@ fn on_create(hwnd: HWND, msg: u32, w_param: WPARAM, l_param: LPARAM) -> LRESULT
     .message
     .lock()
     .unwrap()
-    .replace(Message::Created {
+    .push_back(Message::Created {
       hwnd,
       hinstance: create_struct.hInstance,
     });
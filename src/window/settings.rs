@@ -1,5 +1,23 @@
 use super::{
-  data::{CursorMode, Flow, Fullscreen, LogicalSize, Position, Size, Theme, Visibility},
+  data::{
+    Backdrop,
+    Color,
+    CornerPreference,
+    CursorMode,
+    DpiPolicy,
+    Flow,
+    Fullscreen,
+    LogicalSize,
+    Position,
+    RedrawPacing,
+    Size,
+    Theme,
+    Visibility,
+    WindowLevel,
+  },
+  menu::Menu,
+  suppress::KeySuppression,
+  tray::TrayIcon,
   Window,
 };
 use crate::error::WindowError;
@@ -8,35 +26,101 @@ use crate::error::WindowError;
 #[derive(Debug, Clone)]
 pub struct WindowSettings {
   pub flow: Flow,
+  pub redraw_pacing: RedrawPacing,
+  pub dpi_policy: DpiPolicy,
   pub theme: Theme,
+  pub backdrop: Backdrop,
+  pub corner_preference: CornerPreference,
+  pub border_color: Option<Color>,
+  pub caption_color: Option<Color>,
+  pub caption_text_color: Option<Color>,
   pub visibility: Visibility,
   pub decorations: Visibility,
   pub resizeable: bool,
+  pub transparent: bool,
+  pub opacity: f32,
+  pub cursor_hittest: bool,
+  pub window_level: WindowLevel,
+  pub skip_taskbar: bool,
+  pub tool_window: bool,
+  pub popup: bool,
+  pub modal: bool,
+  pub minimized: bool,
+  pub maximized: bool,
+  pub drag_and_drop: bool,
+  pub menu: Option<Menu>,
+  pub tray_icon: Option<TrayIcon>,
+  pub power_events: bool,
   pub fullscreen: Option<Fullscreen>,
   pub cursor_mode: CursorMode,
+  pub mouse_capture: bool,
+  pub key_suppression: KeySuppression,
   pub close_on_x: bool,
 }
 
 impl Default for WindowSettings {
   fn default() -> Self {
     let flow = Flow::default();
+    let redraw_pacing = RedrawPacing::default();
+    let dpi_policy = DpiPolicy::default();
     let theme = Theme::default();
+    let backdrop = Backdrop::default();
+    let corner_preference = CornerPreference::default();
     let fullscreen = None;
     let cursor_mode = CursorMode::default();
+    let mouse_capture = true;
+    let key_suppression = KeySuppression::default();
     let visibility = Visibility::default();
     let decorations = Visibility::default();
     let resizeable = true;
+    let transparent = false;
+    let opacity = 1.0;
+    let cursor_hittest = true;
+    let window_level = WindowLevel::default();
+    let skip_taskbar = false;
+    let tool_window = false;
+    let popup = false;
+    let modal = false;
+    let minimized = false;
+    let maximized = false;
+    let drag_and_drop = false;
+    let menu = None;
+    let tray_icon = None;
+    let power_events = false;
     let close_on_x = true;
 
     Self {
       flow,
+      redraw_pacing,
+      dpi_policy,
       theme,
+      backdrop,
+      corner_preference,
+      border_color: None,
+      caption_color: None,
+      caption_text_color: None,
       visibility,
       decorations,
       close_on_x,
       fullscreen,
       resizeable,
+      transparent,
+      opacity,
+      cursor_hittest,
+      window_level,
+      skip_taskbar,
+      tool_window,
+      popup,
+      modal,
+      minimized,
+      maximized,
+      drag_and_drop,
+      menu,
+      tray_icon,
+      power_events,
       cursor_mode,
+      mouse_capture,
+      key_suppression,
     }
   }
 }
@@ -47,11 +131,51 @@ impl WindowSettings {
     self
   }
 
+  pub fn with_redraw_pacing(mut self, redraw_pacing: RedrawPacing) -> Self {
+    self.redraw_pacing = redraw_pacing;
+    self
+  }
+
+  pub fn with_dpi_policy(mut self, dpi_policy: DpiPolicy) -> Self {
+    self.dpi_policy = dpi_policy;
+    self
+  }
+
   pub fn with_theme(mut self, theme: Theme) -> Self {
     self.theme = theme;
     self
   }
 
+  /// Requires Windows 11 (build 22000). Mica is the only backdrop available before build 22621.
+  pub fn with_backdrop(mut self, backdrop: Backdrop) -> Self {
+    self.backdrop = backdrop;
+    self
+  }
+
+  /// Requires Windows 11 (build 22000).
+  pub fn with_corner_preference(mut self, corner_preference: CornerPreference) -> Self {
+    self.corner_preference = corner_preference;
+    self
+  }
+
+  /// `None` uses the system default. Requires Windows 11 (build 22000).
+  pub fn with_border_color(mut self, color: Option<Color>) -> Self {
+    self.border_color = color;
+    self
+  }
+
+  /// `None` uses the system default. Requires Windows 11 (build 22000).
+  pub fn with_caption_color(mut self, color: Option<Color>) -> Self {
+    self.caption_color = color;
+    self
+  }
+
+  /// `None` uses the system default. Requires Windows 11 (build 22000).
+  pub fn with_caption_text_color(mut self, color: Option<Color>) -> Self {
+    self.caption_text_color = color;
+    self
+  }
+
   pub fn with_visibility(mut self, visibility: Visibility) -> Self {
     self.visibility = visibility;
     self
@@ -68,11 +192,60 @@ impl WindowSettings {
     self
   }
 
+  /// Applied once the window is first shown.
+  pub fn with_minimized(mut self, minimized: bool) -> Self {
+    self.minimized = minimized;
+    self
+  }
+
+  /// Applied once the window is first shown.
+  pub fn with_maximized(mut self, maximized: bool) -> Self {
+    self.maximized = maximized;
+    self
+  }
+
+  /// Accept files, text, and links dropped onto the window.
+  pub fn with_drag_and_drop(mut self, drag_and_drop: bool) -> Self {
+    self.drag_and_drop = drag_and_drop;
+    self
+  }
+
+  pub fn with_menu(mut self, menu: Option<Menu>) -> Self {
+    self.menu = menu;
+    self
+  }
+
+  pub fn with_tray_icon(mut self, tray_icon: Option<TrayIcon>) -> Self {
+    self.tray_icon = tray_icon;
+    self
+  }
+
+  /// Receive system suspend and resume, session lock and unlock, and display state
+  /// messages.
+  pub fn with_power_events(mut self, power_events: bool) -> Self {
+    self.power_events = power_events;
+    self
+  }
+
   pub fn with_cursor_mode(mut self, cursor_mode: CursorMode) -> Self {
     self.cursor_mode = cursor_mode;
     self
   }
 
+  /// Capture the mouse while a button is held in the client area, so that it is released
+  /// even if the cursor has left the window.
+  pub fn with_mouse_capture(mut self, mouse_capture: bool) -> Self {
+    self.mouse_capture = mouse_capture;
+    self
+  }
+
+  /// Swallow system shortcuts while the window is focused and fullscreen. Everything is
+  /// restored once it is not, including after a panic.
+  pub fn with_key_suppression(mut self, key_suppression: KeySuppression) -> Self {
+    self.key_suppression = key_suppression;
+    self
+  }
+
   pub fn with_close_on_x(mut self, close_on_x: bool) -> Self {
     self.close_on_x = close_on_x;
     self
@@ -82,12 +255,78 @@ impl WindowSettings {
     self.resizeable = resizeable;
     self
   }
+
+  /// Graphics surfaces should be configured with a premultiplied alpha mode for this to
+  /// have an effect.
+  pub fn with_transparent(mut self, transparent: bool) -> Self {
+    self.transparent = transparent;
+    self
+  }
+
+  /// Clamped to `0.0..=1.0`
+  pub fn with_opacity(mut self, opacity: f32) -> Self {
+    self.opacity = opacity.clamp(0.0, 1.0);
+    self
+  }
+
+  pub fn with_cursor_hittest(mut self, hittest: bool) -> Self {
+    self.cursor_hittest = hittest;
+    self
+  }
+
+  pub fn with_window_level(mut self, window_level: WindowLevel) -> Self {
+    self.window_level = window_level;
+    self
+  }
+
+  /// Hide the taskbar button of the window.
+  pub fn with_skip_taskbar(mut self, skip_taskbar: bool) -> Self {
+    self.skip_taskbar = skip_taskbar;
+    self
+  }
+
+  /// Tool windows have a smaller title bar and no taskbar button.
+  pub fn with_tool_window(mut self, tool_window: bool) -> Self {
+    self.tool_window = tool_window;
+    self
+  }
+
+  /// Popup windows have no title bar or taskbar button. Useful for menus and tooltips.
+  pub fn with_popup(mut self, popup: bool) -> Self {
+    self.popup = popup;
+    self
+  }
+
+  /// Disable the owner of the window until this window is closed. Has no effect unless the
+  /// window is built with [`WindowBuilder::with_owner`].
+  pub fn with_modal(mut self, modal: bool) -> Self {
+    self.modal = modal;
+    self
+  }
+}
+
+/// The window a new window is attached to.
+#[derive(Clone)]
+pub(crate) enum Parent {
+  /// Top-level window which stays above and is minimized with its owner.
+  Owner(Window),
+  /// Window embedded within the client area of its parent.
+  Child(Window),
+}
+
+impl Parent {
+  pub(crate) fn window(&self) -> &Window {
+    match self {
+      Parent::Owner(window) | Parent::Child(window) => window,
+    }
+  }
 }
 
 pub struct WindowBuilder {
   title: String,
   size: Size,
   position: Option<Position>,
+  parent: Option<Parent>,
   settings: WindowSettings,
 }
 
@@ -97,6 +336,7 @@ impl Default for WindowBuilder {
       title: "Window".into(),
       size: LogicalSize::new(800.0, 500.0).into(),
       position: None,
+      parent: None,
       settings: WindowSettings::default(),
     }
   }
@@ -132,16 +372,65 @@ impl WindowBuilder {
     self
   }
 
+  /// Owned windows always stay above their owner and are hidden when it is minimized.
+  /// The owner is kept alive for as long as this window exists.
+  pub fn with_owner(mut self, owner: &Window) -> Self {
+    self.parent = Some(Parent::Owner(owner.clone()));
+    self
+  }
+
+  /// Embed the window within the client area of `parent`. Position is relative to the
+  /// client area of the parent. The parent is kept alive for as long as this window exists.
+  pub fn with_parent(mut self, parent: &Window) -> Self {
+    self.parent = Some(Parent::Child(parent.clone()));
+    self
+  }
+
   pub fn with_flow(mut self, flow: Flow) -> Self {
     self.settings = self.settings.with_flow(flow);
     self
   }
 
+  pub fn with_redraw_pacing(mut self, redraw_pacing: RedrawPacing) -> Self {
+    self.settings = self.settings.with_redraw_pacing(redraw_pacing);
+    self
+  }
+
+  pub fn with_dpi_policy(mut self, dpi_policy: DpiPolicy) -> Self {
+    self.settings = self.settings.with_dpi_policy(dpi_policy);
+    self
+  }
+
   pub fn with_theme(mut self, theme: Theme) -> Self {
     self.settings = self.settings.with_theme(theme);
     self
   }
 
+  pub fn with_backdrop(mut self, backdrop: Backdrop) -> Self {
+    self.settings = self.settings.with_backdrop(backdrop);
+    self
+  }
+
+  pub fn with_corner_preference(mut self, corner_preference: CornerPreference) -> Self {
+    self.settings = self.settings.with_corner_preference(corner_preference);
+    self
+  }
+
+  pub fn with_border_color(mut self, color: Option<Color>) -> Self {
+    self.settings = self.settings.with_border_color(color);
+    self
+  }
+
+  pub fn with_caption_color(mut self, color: Option<Color>) -> Self {
+    self.settings = self.settings.with_caption_color(color);
+    self
+  }
+
+  pub fn with_caption_text_color(mut self, color: Option<Color>) -> Self {
+    self.settings = self.settings.with_caption_text_color(color);
+    self
+  }
+
   pub fn with_visibility(mut self, visibility: Visibility) -> Self {
     self.settings = self.settings.with_visibility(visibility);
     self
@@ -158,11 +447,51 @@ impl WindowBuilder {
     self
   }
 
+  pub fn with_minimized(mut self, minimized: bool) -> Self {
+    self.settings = self.settings.with_minimized(minimized);
+    self
+  }
+
+  pub fn with_maximized(mut self, maximized: bool) -> Self {
+    self.settings = self.settings.with_maximized(maximized);
+    self
+  }
+
+  pub fn with_drag_and_drop(mut self, drag_and_drop: bool) -> Self {
+    self.settings = self.settings.with_drag_and_drop(drag_and_drop);
+    self
+  }
+
+  pub fn with_menu(mut self, menu: Option<Menu>) -> Self {
+    self.settings = self.settings.with_menu(menu);
+    self
+  }
+
+  pub fn with_tray_icon(mut self, tray_icon: Option<TrayIcon>) -> Self {
+    self.settings = self.settings.with_tray_icon(tray_icon);
+    self
+  }
+
+  pub fn with_power_events(mut self, power_events: bool) -> Self {
+    self.settings = self.settings.with_power_events(power_events);
+    self
+  }
+
   pub fn with_cursor_mode(mut self, cursor_mode: CursorMode) -> Self {
     self.settings = self.settings.with_cursor_mode(cursor_mode);
     self
   }
 
+  pub fn with_mouse_capture(mut self, mouse_capture: bool) -> Self {
+    self.settings = self.settings.with_mouse_capture(mouse_capture);
+    self
+  }
+
+  pub fn with_key_suppression(mut self, key_suppression: KeySuppression) -> Self {
+    self.settings = self.settings.with_key_suppression(key_suppression);
+    self
+  }
+
   pub fn with_close_on_x(mut self, close_on_x: bool) -> Self {
     self.settings = self.settings.with_close_on_x(close_on_x);
     self
@@ -173,7 +502,47 @@ impl WindowBuilder {
     self
   }
 
+  pub fn with_transparent(mut self, transparent: bool) -> Self {
+    self.settings = self.settings.with_transparent(transparent);
+    self
+  }
+
+  pub fn with_opacity(mut self, opacity: f32) -> Self {
+    self.settings = self.settings.with_opacity(opacity);
+    self
+  }
+
+  pub fn with_cursor_hittest(mut self, hittest: bool) -> Self {
+    self.settings = self.settings.with_cursor_hittest(hittest);
+    self
+  }
+
+  pub fn with_window_level(mut self, window_level: WindowLevel) -> Self {
+    self.settings = self.settings.with_window_level(window_level);
+    self
+  }
+
+  pub fn with_skip_taskbar(mut self, skip_taskbar: bool) -> Self {
+    self.settings = self.settings.with_skip_taskbar(skip_taskbar);
+    self
+  }
+
+  pub fn with_tool_window(mut self, tool_window: bool) -> Self {
+    self.settings = self.settings.with_tool_window(tool_window);
+    self
+  }
+
+  pub fn with_popup(mut self, popup: bool) -> Self {
+    self.settings = self.settings.with_popup(popup);
+    self
+  }
+
+  pub fn with_modal(mut self, modal: bool) -> Self {
+    self.settings = self.settings.with_modal(modal);
+    self
+  }
+
   pub fn build(self) -> Result<Window, WindowError> {
-    Window::new(self.title, self.size, self.position, self.settings)
+    Window::new(self.title, self.size, self.position, self.parent, self.settings)
   }
 }
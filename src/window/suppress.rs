@@ -0,0 +1,352 @@
+use std::{
+  sync::{
+    atomic::{AtomicU8, Ordering},
+    mpsc,
+    Mutex,
+    MutexGuard,
+    Once,
+    PoisonError,
+  },
+  thread::JoinHandle,
+};
+
+use windows::Win32::{
+  Foundation::{HWND, LPARAM, LRESULT, WPARAM},
+  System::{LibraryLoader::GetModuleHandleW, Threading::GetCurrentThreadId},
+  UI::{
+    Accessibility::{self, FILTERKEYS, STICKYKEYS, TOGGLEKEYS},
+    Input::KeyboardAndMouse,
+    WindowsAndMessaging::{
+      self,
+      CallNextHookEx,
+      GetMessageW,
+      PeekMessageW,
+      PostThreadMessageW,
+      SetWindowsHookExW,
+      SystemParametersInfoW,
+      UnhookWindowsHookEx,
+      KBDLLHOOKSTRUCT,
+      MSG,
+      SYSTEM_PARAMETERS_INFO_ACTION,
+    },
+  },
+};
+
+use crate::utilities::is_flag_set;
+
+/// System shortcuts swallowed while the window is focused and fullscreen, so that they do
+/// not minimize it or interrupt the user.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct KeySuppression {
+  /// The Windows keys, which open the Start menu. The window does not receive them either.
+  pub windows_key: bool,
+  /// Alt+Tab and Alt+Esc.
+  pub alt_tab: bool,
+  /// The shortcuts that prompt to turn on Sticky Keys, Filter Keys and Toggle Keys, such as
+  /// pressing Shift five times.
+  pub accessibility_shortcuts: bool,
+}
+
+impl KeySuppression {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_windows_key(mut self, windows_key: bool) -> Self {
+    self.windows_key = windows_key;
+    self
+  }
+
+  pub fn with_alt_tab(mut self, alt_tab: bool) -> Self {
+    self.alt_tab = alt_tab;
+    self
+  }
+
+  pub fn with_accessibility_shortcuts(mut self, accessibility_shortcuts: bool) -> Self {
+    self.accessibility_shortcuts = accessibility_shortcuts;
+    self
+  }
+
+  fn needs_hook(self) -> bool {
+    self.windows_key || self.alt_tab
+  }
+
+  fn hook_flags(self) -> u8 {
+    let mut flags = 0;
+    if self.windows_key {
+      flags |= WINDOWS_KEY;
+    }
+    if self.alt_tab {
+      flags |= ALT_TAB;
+    }
+    flags
+  }
+}
+
+const WINDOWS_KEY: u8 = 1 << 0;
+const ALT_TAB: u8 = 1 << 1;
+
+/// Keys swallowed by the keyboard hook. Only one window is focused at a time, so the
+/// suppression is shared by the whole process.
+static HOOK_FLAGS: AtomicU8 = AtomicU8::new(0);
+static STATE: Mutex<Option<State>> = Mutex::new(None);
+
+struct State {
+  /// Window whose suppression is applied.
+  hwnd: HWND,
+  hook: Option<HookThread>,
+  /// Settings to restore once the accessibility shortcuts are no longer suppressed.
+  accessibility: Option<AccessibilityKeys>,
+}
+
+/// Applies the suppression of the window, or releases it if `None` and the window is the
+/// one it was applied for.
+pub(crate) fn apply(hwnd: HWND, suppression: Option<KeySuppression>) {
+  let mut state = lock_state();
+  let Some(suppression) = suppression else {
+    if state.as_ref().is_some_and(|state| state.hwnd == hwnd) {
+      release(&mut state);
+    }
+    return;
+  };
+
+  restore_on_panic();
+  let state = state.get_or_insert_with(|| State {
+    hwnd,
+    hook: None,
+    accessibility: None,
+  });
+  state.hwnd = hwnd;
+
+  HOOK_FLAGS.store(suppression.hook_flags(), Ordering::Relaxed);
+  match suppression.needs_hook() {
+    true if state.hook.is_none() => state.hook = HookThread::spawn(),
+    false => drop(state.hook.take()),
+    _ => (),
+  }
+  match suppression.accessibility_shortcuts {
+    true if state.accessibility.is_none() => {
+      state.accessibility = Some(AccessibilityKeys::disable_shortcuts())
+    }
+    false => {
+      if let Some(accessibility) = state.accessibility.take() {
+        accessibility.apply();
+      }
+    }
+    _ => (),
+  }
+}
+
+fn release(state: &mut Option<State>) {
+  HOOK_FLAGS.store(0, Ordering::Relaxed);
+  if let Some(state) = state.take() {
+    drop(state.hook);
+    if let Some(accessibility) = state.accessibility {
+      accessibility.apply();
+    }
+  }
+}
+
+fn lock_state() -> MutexGuard<'static, Option<State>> {
+  STATE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The accessibility settings outlive the process, so they are restored before a panic
+/// unwinds or aborts.
+fn restore_on_panic() {
+  static ONCE: Once = Once::new();
+  ONCE.call_once(|| {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+      HOOK_FLAGS.store(0, Ordering::Relaxed);
+      // the lock may be held by the panicking thread
+      let state = match STATE.try_lock() {
+        Ok(state) => Some(state),
+        Err(std::sync::TryLockError::Poisoned(e)) => Some(e.into_inner()),
+        Err(std::sync::TryLockError::WouldBlock) => None,
+      };
+      if let Some(accessibility) =
+        state.and_then(|mut state| state.as_mut()?.accessibility.take())
+      {
+        accessibility.apply();
+      }
+      previous(info);
+    }));
+  });
+}
+
+/// Thread running a low-level keyboard hook. Hooks are called from the message loop of
+/// the thread that installed them, which the window thread cannot keep responsive while it
+/// waits on the main thread.
+struct HookThread {
+  thread_id: u32,
+  handle: Option<JoinHandle<()>>,
+}
+
+impl HookThread {
+  fn spawn() -> Option<Self> {
+    let (sender, receiver) = mpsc::channel();
+    let handle = std::thread::Builder::new()
+      .name("witer keyboard hook".to_owned())
+      .spawn(move || {
+        let mut msg = MSG::default();
+        // creates the message queue before the thread id is shared
+        let _ =
+          unsafe { PeekMessageW(&mut msg, None, 0, 0, WindowsAndMessaging::PM_NOREMOVE) };
+
+        let hook = unsafe { GetModuleHandleW(None) }.and_then(|module| unsafe {
+          SetWindowsHookExW(
+            WindowsAndMessaging::WH_KEYBOARD_LL,
+            Some(keyboard_hook),
+            module,
+            0,
+          )
+        });
+        let hook = match hook {
+          Ok(hook) => hook,
+          Err(e) => {
+            tracing::error!("{e}");
+            let _ = sender.send(None);
+            return;
+          }
+        };
+        let _ = sender.send(Some(unsafe { GetCurrentThreadId() }));
+
+        while unsafe { GetMessageW(&mut msg, None, 0, 0) }.0 > 0 {}
+        let _ = unsafe { UnhookWindowsHookEx(hook) };
+      });
+
+    let handle = match handle {
+      Ok(handle) => handle,
+      Err(e) => {
+        tracing::error!("{e}");
+        return None;
+      }
+    };
+    let thread_id = receiver.recv().ok().flatten()?;
+    Some(Self {
+      thread_id,
+      handle: Some(handle),
+    })
+  }
+}
+
+impl Drop for HookThread {
+  fn drop(&mut self) {
+    let _ = unsafe {
+      PostThreadMessageW(
+        self.thread_id,
+        WindowsAndMessaging::WM_QUIT,
+        WPARAM(0),
+        LPARAM(0),
+      )
+    };
+    if let Some(handle) = self.handle.take() {
+      let _ = handle.join();
+    }
+  }
+}
+
+unsafe extern "system" fn keyboard_hook(
+  code: i32,
+  wparam: WPARAM,
+  lparam: LPARAM,
+) -> LRESULT {
+  if code == WindowsAndMessaging::HC_ACTION as i32 {
+    let info = unsafe { &*(lparam.0 as *const KBDLLHOOKSTRUCT) };
+    let flags = HOOK_FLAGS.load(Ordering::Relaxed);
+    let key = KeyboardAndMouse::VIRTUAL_KEY(info.vkCode as u16);
+    let alt = is_flag_set(info.flags.0, WindowsAndMessaging::LLKHF_ALTDOWN.0);
+
+    let swallow = match key {
+      KeyboardAndMouse::VK_LWIN | KeyboardAndMouse::VK_RWIN => flags & WINDOWS_KEY != 0,
+      KeyboardAndMouse::VK_TAB | KeyboardAndMouse::VK_ESCAPE => {
+        alt && flags & ALT_TAB != 0
+      }
+      _ => false,
+    };
+    if swallow {
+      return LRESULT(1);
+    }
+  }
+
+  unsafe { CallNextHookEx(None, code, wparam, lparam) }
+}
+
+/// Sticky Keys, Filter Keys and Toggle Keys settings.
+#[derive(Clone, Copy)]
+struct AccessibilityKeys {
+  sticky: STICKYKEYS,
+  filter: FILTERKEYS,
+  toggle: TOGGLEKEYS,
+}
+
+impl AccessibilityKeys {
+  /// Disables the shortcuts of the features that are turned off, returning the previous
+  /// settings. Features that are on keep their shortcuts so that they can be turned off.
+  fn disable_shortcuts() -> Self {
+    let previous = Self::current();
+    let mut disabled = previous;
+
+    let sticky_flags = &mut disabled.sticky.dwFlags;
+    if !is_flag_set(sticky_flags.0, Accessibility::SKF_STICKYKEYSON.0) {
+      *sticky_flags &=
+        !(Accessibility::SKF_HOTKEYACTIVE | Accessibility::SKF_CONFIRMHOTKEY);
+    }
+    let filter_flags = &mut disabled.filter.dwFlags;
+    if !is_flag_set(*filter_flags, WindowsAndMessaging::FKF_FILTERKEYSON) {
+      *filter_flags &=
+        !(WindowsAndMessaging::FKF_HOTKEYACTIVE | WindowsAndMessaging::FKF_CONFIRMHOTKEY);
+    }
+    let toggle_flags = &mut disabled.toggle.dwFlags;
+    if !is_flag_set(*toggle_flags, WindowsAndMessaging::TKF_TOGGLEKEYSON) {
+      *toggle_flags &=
+        !(WindowsAndMessaging::TKF_HOTKEYACTIVE | WindowsAndMessaging::TKF_CONFIRMHOTKEY);
+    }
+
+    disabled.apply();
+    previous
+  }
+
+  fn current() -> Self {
+    let mut keys = Self {
+      sticky: STICKYKEYS {
+        cbSize: std::mem::size_of::<STICKYKEYS>() as u32,
+        ..Default::default()
+      },
+      filter: FILTERKEYS {
+        cbSize: std::mem::size_of::<FILTERKEYS>() as u32,
+        ..Default::default()
+      },
+      toggle: TOGGLEKEYS {
+        cbSize: std::mem::size_of::<TOGGLEKEYS>() as u32,
+        ..Default::default()
+      },
+    };
+    system_parameter(WindowsAndMessaging::SPI_GETSTICKYKEYS, &mut keys.sticky);
+    system_parameter(WindowsAndMessaging::SPI_GETFILTERKEYS, &mut keys.filter);
+    system_parameter(WindowsAndMessaging::SPI_GETTOGGLEKEYS, &mut keys.toggle);
+    keys
+  }
+
+  fn apply(mut self) {
+    system_parameter(WindowsAndMessaging::SPI_SETSTICKYKEYS, &mut self.sticky);
+    system_parameter(WindowsAndMessaging::SPI_SETFILTERKEYS, &mut self.filter);
+    system_parameter(WindowsAndMessaging::SPI_SETTOGGLEKEYS, &mut self.toggle);
+  }
+}
+
+/// Gets or sets a setting stored in a structure that starts with its size. Changes are not
+/// saved to the user profile.
+fn system_parameter<T>(action: SYSTEM_PARAMETERS_INFO_ACTION, value: &mut T) {
+  if let Err(e) = unsafe {
+    SystemParametersInfoW(
+      action,
+      std::mem::size_of::<T>() as u32,
+      Some(value as *mut T as *mut std::ffi::c_void),
+      Default::default(),
+    )
+  } {
+    tracing::error!("{e}");
+  }
+}
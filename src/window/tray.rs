@@ -0,0 +1,252 @@
+use std::sync::OnceLock;
+
+use windows::{
+  core::w,
+  Win32::{
+    Foundation::HWND,
+    UI::{
+      Shell::{self, Shell_NotifyIconW, NOTIFYICONDATAW, NOTIFYICONDATAW_0},
+      WindowsAndMessaging::{self, LoadIconW, RegisterWindowMessageW},
+    },
+  },
+};
+
+use super::{
+  data::PhysicalPosition,
+  icon::{Icon, IconHandle},
+  menu::Menu,
+};
+use crate::error::WindowError;
+
+/// Messages sent by the tray icon of the window. Positions are in screen coordinates.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TrayEvent {
+  LeftClick {
+    position: PhysicalPosition,
+  },
+  RightClick {
+    position: PhysicalPosition,
+  },
+  DoubleClick {
+    position: PhysicalPosition,
+  },
+  BalloonClicked,
+  /// The balloon was closed or timed out without being clicked.
+  BalloonDismissed,
+}
+
+/// Icon shown in the notification area while set on a window with
+/// [`Window::set_tray_icon`](super::Window::set_tray_icon).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TrayIcon {
+  /// Uses the default application icon if not set.
+  pub icon: Option<Icon>,
+  pub tooltip: String,
+  /// Shown when the icon is right-clicked. Selected items are sent as
+  /// [`Message::MenuCommand`](super::message::Message::MenuCommand).
+  pub menu: Option<Menu>,
+}
+
+impl TrayIcon {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_icon(mut self, icon: Option<Icon>) -> Self {
+    self.icon = icon;
+    self
+  }
+
+  /// Truncated to 127 characters.
+  pub fn with_tooltip(mut self, tooltip: impl Into<String>) -> Self {
+    self.tooltip = tooltip.into();
+    self
+  }
+
+  pub fn with_menu(mut self, menu: Option<Menu>) -> Self {
+    self.menu = menu;
+    self
+  }
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BalloonIcon {
+  #[default]
+  None,
+  Info,
+  Warning,
+  Error,
+  /// Uses the icon of the tray icon.
+  Tray,
+}
+
+/// Notification shown by the tray icon.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Balloon {
+  pub title: String,
+  pub text: String,
+  pub icon: BalloonIcon,
+  pub silent: bool,
+}
+
+impl Balloon {
+  pub fn new(title: impl Into<String>, text: impl Into<String>) -> Self {
+    Self {
+      title: title.into(),
+      text: text.into(),
+      icon: BalloonIcon::default(),
+      silent: false,
+    }
+  }
+
+  pub fn with_icon(mut self, icon: BalloonIcon) -> Self {
+    self.icon = icon;
+    self
+  }
+
+  pub fn with_silent(mut self, silent: bool) -> Self {
+    self.silent = silent;
+    self
+  }
+}
+
+/// Tray icon currently shown by the window.
+pub(crate) struct Tray {
+  pub settings: TrayIcon,
+  icon: Option<IconHandle>,
+}
+
+impl Tray {
+  /// Each window has at most one tray icon.
+  const ID: u32 = 1;
+  /// Callback message sent to the window by the tray icon.
+  pub(crate) const MESSAGE_ID: u32 = WindowsAndMessaging::WM_USER + 70;
+
+  /// Adds the icon to the notification area, or updates it if it is already shown.
+  pub(crate) fn show(
+    hwnd: HWND,
+    settings: TrayIcon,
+    previous: Option<&Tray>,
+  ) -> Result<Self, WindowError> {
+    let icon = settings.icon.as_ref().map(Icon::to_handle).transpose()?;
+    let tray = Self { settings, icon };
+
+    let data = tray.icon_data(hwnd)?;
+    match previous {
+      Some(_) => unsafe { Shell_NotifyIconW(Shell::NIM_MODIFY, &data) }.ok()?,
+      None => tray.add(&data)?,
+    }
+
+    Ok(tray)
+  }
+
+  /// Adds the icon again after Explorer restarts.
+  pub(crate) fn restore(&self, hwnd: HWND) -> Result<(), WindowError> {
+    self.add(&self.icon_data(hwnd)?)
+  }
+
+  pub(crate) fn remove(hwnd: HWND) {
+    let data = Self::notify_icon_data(hwnd);
+    let _ = unsafe { Shell_NotifyIconW(Shell::NIM_DELETE, &data) };
+  }
+
+  pub(crate) fn show_balloon(
+    &self,
+    hwnd: HWND,
+    balloon: &Balloon,
+  ) -> Result<(), WindowError> {
+    let mut data = Self::notify_icon_data(hwnd);
+    data.uFlags |= Shell::NIF_INFO;
+    copy_wide(&mut data.szInfoTitle, &balloon.title);
+    copy_wide(&mut data.szInfo, &balloon.text);
+    data.dwInfoFlags = match balloon.icon {
+      BalloonIcon::None => Shell::NIIF_NONE,
+      BalloonIcon::Info => Shell::NIIF_INFO,
+      BalloonIcon::Warning => Shell::NIIF_WARNING,
+      BalloonIcon::Error => Shell::NIIF_ERROR,
+      BalloonIcon::Tray => Shell::NIIF_USER | Shell::NIIF_LARGE_ICON,
+    };
+    if balloon.silent {
+      data.dwInfoFlags |= Shell::NIIF_NOSOUND;
+    }
+
+    unsafe { Shell_NotifyIconW(Shell::NIM_MODIFY, &data) }.ok()?;
+    Ok(())
+  }
+
+  /// Decodes the callback message, which uses the `NOTIFYICON_VERSION_4` layout.
+  pub(crate) fn event(wparam: usize, lparam: isize) -> Option<TrayEvent> {
+    let position = PhysicalPosition::new(
+      wparam as u16 as i16 as i32,
+      (wparam >> 16) as u16 as i16 as i32,
+    );
+    match lparam as u32 & 0xFFFF {
+      WindowsAndMessaging::WM_LBUTTONUP => Some(TrayEvent::LeftClick { position }),
+      WindowsAndMessaging::WM_RBUTTONUP => Some(TrayEvent::RightClick { position }),
+      WindowsAndMessaging::WM_LBUTTONDBLCLK => Some(TrayEvent::DoubleClick { position }),
+      Shell::NIN_BALLOONUSERCLICK => Some(TrayEvent::BalloonClicked),
+      Shell::NIN_BALLOONTIMEOUT | Shell::NIN_BALLOONHIDE => {
+        Some(TrayEvent::BalloonDismissed)
+      }
+      _ => None,
+    }
+  }
+
+  /// Returns the anchor point of the menu if the callback message asks for it.
+  pub(crate) fn context_menu_position(
+    wparam: usize,
+    lparam: isize,
+  ) -> Option<(i32, i32)> {
+    (lparam as u32 & 0xFFFF == WindowsAndMessaging::WM_CONTEXTMENU)
+      .then_some((wparam as u16 as i16 as i32, (wparam >> 16) as u16 as i16 as i32))
+  }
+
+  fn add(&self, data: &NOTIFYICONDATAW) -> Result<(), WindowError> {
+    unsafe { Shell_NotifyIconW(Shell::NIM_ADD, data) }.ok()?;
+
+    let mut data = Self::notify_icon_data(data.hWnd);
+    data.Anonymous = NOTIFYICONDATAW_0 {
+      uVersion: Shell::NOTIFYICON_VERSION_4,
+    };
+    unsafe { Shell_NotifyIconW(Shell::NIM_SETVERSION, &data) }.ok()?;
+
+    Ok(())
+  }
+
+  fn icon_data(&self, hwnd: HWND) -> Result<NOTIFYICONDATAW, WindowError> {
+    let mut data = Self::notify_icon_data(hwnd);
+    data.uFlags |=
+      Shell::NIF_MESSAGE | Shell::NIF_ICON | Shell::NIF_TIP | Shell::NIF_SHOWTIP;
+    data.uCallbackMessage = Self::MESSAGE_ID;
+    data.hIcon = match &self.icon {
+      Some(icon) => icon.0,
+      None => unsafe { LoadIconW(None, WindowsAndMessaging::IDI_APPLICATION) }?,
+    };
+    copy_wide(&mut data.szTip, &self.settings.tooltip);
+    Ok(data)
+  }
+
+  fn notify_icon_data(hwnd: HWND) -> NOTIFYICONDATAW {
+    NOTIFYICONDATAW {
+      cbSize: std::mem::size_of::<NOTIFYICONDATAW>() as u32,
+      hWnd: hwnd,
+      uID: Self::ID,
+      ..Default::default()
+    }
+  }
+}
+
+/// Message broadcast to all top-level windows when the taskbar is created, such as after
+/// Explorer restarts.
+pub(crate) fn taskbar_created_message() -> u32 {
+  static MESSAGE: OnceLock<u32> = OnceLock::new();
+  *MESSAGE.get_or_init(|| unsafe { RegisterWindowMessageW(w!("TaskbarCreated")) })
+}
+
+/// Copies as much of the text as fits into the null-terminated buffer.
+fn copy_wide(buffer: &mut [u16], text: &str) {
+  let len = buffer.len() - 1;
+  for (dst, src) in buffer.iter_mut().zip(text.encode_utf16().take(len)) {
+    *dst = src;
+  }
+}